@@ -0,0 +1,102 @@
+use crate::greetd_client::{self, GreetdClient, GreetdResult};
+use greetd_ipc::Response;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Boxed future returned by [`Authenticator`] methods, so the trait can be
+/// used as a trait object
+pub type AuthFuture<'a, T> = Pin<Box<dyn Future<Output = GreetdResult<T>> + Send + 'a>>;
+
+/// Whether a visible PAM prompt is asking for the login name. Only the
+/// plain prompts pam_unix and friends use count ("login:", "Username:",
+/// "User name:"), so a prompt like "OTP for user alice:" is never answered
+/// with the username.
+pub fn is_username_prompt(prompt: &str) -> bool {
+    let prompt = prompt.trim().to_lowercase();
+    ["login:", "username:", "user name:"].contains(&prompt.as_str())
+}
+
+/// Login backend behind the greeter UI. The conversation follows greetd's
+/// model: start one for a user, answer its prompts until it reports
+/// `Success`, then start the session (or cancel). [`GreetdAuthenticator`]
+/// is the default; a mock or another backend can be passed to
+/// [`run_greeter_with_auth`](crate::run_greeter_with_auth).
+pub trait Authenticator: Send {
+    /// Begin a login conversation for `username`, returning the first reply
+    fn create_session<'a>(&'a mut self, username: &'a str) -> AuthFuture<'a, Response>;
+
+    /// Answer the latest prompt (`None` acknowledges Info/Error messages)
+    fn post_response(&mut self, response: Option<String>) -> AuthFuture<'_, Response>;
+
+    /// Start the session after a successful conversation
+    fn start_session(&mut self, cmd: Vec<String>, env: Vec<String>) -> AuthFuture<'_, ()>;
+
+    /// Abandon the conversation in progress
+    fn cancel_session(&mut self) -> AuthFuture<'_, ()>;
+
+    /// Drop any connection; the next conversation starts afresh
+    fn close(&mut self);
+
+    /// Something the backend recovered from that the user may want to know
+    /// about, reported once
+    fn take_notice(&mut self) -> Option<String> {
+        None
+    }
+}
+
+/// [`Authenticator`] talking to greetd, with a fresh connection per
+/// conversation
+pub struct GreetdAuthenticator {
+    target: String,
+    client: Option<GreetdClient>,
+}
+
+impl GreetdAuthenticator {
+    /// Authenticate through greetd at `target` (see [`GreetdClient::connect_to`])
+    pub fn new(target: String) -> Self {
+        Self { target, client: None }
+    }
+
+    /// Authenticate through the configured socket, else `GREETD_SOCK`
+    pub fn from_config(socket: Option<&str>) -> Self {
+        Self::new(greetd_client::socket_target(socket))
+    }
+
+    fn connected(&mut self) -> GreetdResult<&mut GreetdClient> {
+        self.client.as_mut().ok_or_else(|| {
+            greetd_client::GreetdError::Protocol("No login conversation in progress".into())
+        })
+    }
+}
+
+impl Authenticator for GreetdAuthenticator {
+    fn create_session<'a>(&'a mut self, username: &'a str) -> AuthFuture<'a, Response> {
+        Box::pin(async move {
+            let client = self.client.insert(GreetdClient::connect_to(&self.target).await?);
+            client.create_session(username).await
+        })
+    }
+
+    fn post_response(&mut self, response: Option<String>) -> AuthFuture<'_, Response> {
+        Box::pin(async move { self.connected()?.post_response(response).await })
+    }
+
+    fn start_session(&mut self, cmd: Vec<String>, env: Vec<String>) -> AuthFuture<'_, ()> {
+        Box::pin(async move { self.connected()?.start_session(cmd, env).await })
+    }
+
+    fn cancel_session(&mut self) -> AuthFuture<'_, ()> {
+        Box::pin(async move { self.connected()?.cancel_session().await })
+    }
+
+    fn close(&mut self) {
+        self.client = None;
+    }
+
+    fn take_notice(&mut self) -> Option<String> {
+        self.client
+            .as_mut()
+            .is_some_and(GreetdClient::take_cancelled_stale)
+            .then(|| "Cancelled a stale greetd session left by an earlier greeter".to_string())
+    }
+}
@@ -0,0 +1,49 @@
+//! `ui.allow_brightness`: change the screen backlight from the login screen
+//! through `/sys/class/backlight`. Writing there usually needs a udev rule
+//! granting the greeter user access.
+
+use std::path::{Path, PathBuf};
+
+/// Where the kernel lists backlight devices
+const BACKLIGHT_DIR: &str = "/sys/class/backlight";
+
+/// Change the brightness of the first backlight device by `step` percent of
+/// its range; returns the new level in percent
+pub fn adjust(step: i32) -> Result<u8, String> {
+    let mut devices: Vec<PathBuf> = std::fs::read_dir(BACKLIGHT_DIR)
+        .map_err(|e| format!("{}: {}", BACKLIGHT_DIR, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    devices.sort();
+    let device = devices.first().ok_or("no backlight device")?;
+    adjust_device(device, step)
+}
+
+/// [`adjust`] for one device directory holding `brightness` and
+/// `max_brightness`. The level never goes below 1, so the screen stays lit,
+/// and always moves by at least one unit on panels with only a few levels.
+pub fn adjust_device(device: &Path, step: i32) -> Result<u8, String> {
+    let read = |name: &str| {
+        let path = device.join(name);
+        std::fs::read_to_string(&path)
+            .map_err(|e| format!("{}: {}", path.display(), e))?
+            .trim()
+            .parse::<i64>()
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    };
+    let max = read("max_brightness")?.max(1);
+    let current = read("brightness")?;
+    let delta = match max * step as i64 / 100 {
+        0 => (step as i64).signum(),
+        delta => delta,
+    };
+    let new = (current + delta).clamp(1, max);
+    let path = device.join("brightness");
+    std::fs::write(&path, new.to_string()).map_err(|e| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => {
+            format!("{}: permission denied (needs a udev rule for the greeter user)", path.display())
+        }
+        _ => format!("{}: {}", path.display(), e),
+    })?;
+    Ok((new * 100 / max) as u8)
+}
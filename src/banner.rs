@@ -0,0 +1,45 @@
+//! `ui.banner_file`: a legal notice or message of the day shown with the
+//! login form. The file is re-read whenever its mtime changes, so editing it
+//! shows up on the next tick.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Contents of the banner file, as of its last seen modification time
+pub struct Banner {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    text: String,
+}
+
+impl Banner {
+    /// Read `path` now; a missing or unreadable file shows no banner
+    pub fn new(path: &Path) -> Self {
+        let mut banner = Self { path: path.to_path_buf(), modified: None, text: String::new() };
+        banner.reload();
+        banner
+    }
+
+    /// Re-read the file if its mtime changed; returns whether the text changed
+    pub fn refresh(&mut self) -> bool {
+        let modified = std::fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        if modified == self.modified {
+            return false;
+        }
+        let old = std::mem::take(&mut self.text);
+        self.reload();
+        self.text != old
+    }
+
+    fn reload(&mut self) {
+        self.modified = std::fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        self.text = std::fs::read_to_string(&self.path)
+            .map(|text| text.trim_end().replace('\t', "    "))
+            .unwrap_or_default();
+    }
+
+    /// The banner text; empty when there is nothing to show
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
@@ -1,4 +1,4 @@
-use crate::config::{load_config_from, Config, InputConfig, MonitorConfig};
+use crate::config::{config_path, load_config_from, Config, InputConfig, MonitorConfig};
 use std::error::Error;
 use std::fmt::Write as _;
 use std::os::unix::process::CommandExt;
@@ -43,9 +43,9 @@ pub fn run(args: &BootstrapArgs) {
                 .config
                 .as_deref()
                 .map(|p| p.display().to_string())
-                .unwrap_or_else(|| "/etc/hypr-greeter/config.toml".to_string());
+                .unwrap_or_else(|| config_path().display().to_string());
             eprintln!("hypr-greeter: failed to parse {}: {}", path, e);
-            std::process::exit(1);
+            std::process::exit(crate::ExitCode::ConfigError.code());
         }
     };
 
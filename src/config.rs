@@ -1,6 +1,8 @@
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::path::{Path, PathBuf};
+use unicode_width::UnicodeWidthStr;
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,12 +10,36 @@ pub struct Config {
     /// Last logged in username (loaded from state file, not from config)
     #[serde(skip)]
     pub last_user: Option<String>,
+    /// Time of the last successful login (loaded from state file, not from config)
+    #[serde(skip)]
+    pub last_login: Option<DateTime<Local>>,
+    /// Name of the session last logged into (loaded from state file, not from config)
+    #[serde(skip)]
+    pub last_session: Option<String>,
+    /// Why the config files were ignored, when running on the built-in defaults
+    #[serde(skip)]
+    pub load_error: Option<String>,
+    /// Whether the state file was read and merged in (not from config)
+    #[serde(skip)]
+    pub state_loaded: bool,
+    /// End the greeter after the first login attempt, failed or not
+    /// (`--once`, not from config)
+    #[serde(skip)]
+    pub once: bool,
     /// Username to autofill at startup (if set, overrides last_user)
     #[serde(default)]
     pub default_user: Option<String>,
     /// If true, disables autofilling username at startup
     #[serde(default)]
     pub disable_autofill: Option<bool>,
+    /// greetd socket to use instead of `GREETD_SOCK`: a path, `@name` for an
+    /// abstract socket, or `tcp:host:port` with the `tcp` feature
+    #[serde(default)]
+    pub greetd_socket: Option<String>,
+    /// `KEY=VALUE` variables set for every session; a session's own `env`
+    /// overrides them
+    #[serde(default)]
+    pub session_env: Vec<String>,
 
     /// Monitor configuration for multi-monitor support
     #[serde(default)]
@@ -23,6 +49,10 @@ pub struct Config {
     #[serde(default = "default_sessions")]
     pub sessions: Vec<Session>,
 
+    /// Options applied to every session command before launch
+    #[serde(default)]
+    pub session: SessionOptions,
+
     /// Keyboard input configuration passed through to Hyprland
     #[serde(default)]
     pub input: InputConfig,
@@ -34,6 +64,14 @@ pub struct Config {
     /// Security settings
     #[serde(default)]
     pub security: SecurityConfig,
+
+    /// Power actions (F1/F2)
+    #[serde(default)]
+    pub power: PowerConfig,
+
+    /// Commands run on login events
+    #[serde(default)]
+    pub hooks: HooksConfig,
 }
 
 /// Monitor configuration for Hyprland
@@ -68,12 +106,53 @@ fn default_scale() -> f64 {
 }
 
 /// Session/Window Manager configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Session {
     /// Display name in the UI
     pub name: String,
     /// Command to execute
     pub command: String,
+    /// Run the command through `/bin/sh -lc` instead of splitting it into argv
+    #[serde(default)]
+    pub shell: bool,
+    /// Ask for a second Enter before logging into this session
+    #[serde(default)]
+    pub confirm: bool,
+    /// `KEY=VALUE` variables for this session only
+    #[serde(default)]
+    pub env: Vec<String>,
+    /// Directory to start the session in (greetd itself starts it in the
+    /// user's home); applied with a `cd` in a `/bin/sh` wrapper
+    #[serde(default)]
+    pub cwd: Option<String>,
+}
+
+impl Session {
+    /// A plain exec session; set the other fields with struct update syntax
+    pub fn new(name: &str, command: &str) -> Self {
+        Self { name: name.to_string(), command: command.to_string(), ..Default::default() }
+    }
+}
+
+/// Options applied when building the session command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionOptions {
+    /// Fail the login instead of expanding undefined `$VAR`s to an empty string
+    #[serde(default)]
+    pub strict_env: bool,
+    /// Pass `/etc/environment` and `~/.config/environment.d/*.conf` to the session
+    #[serde(default = "default_true")]
+    pub load_environment_files: bool,
+    /// Also offer sessions installed in /usr/share/{wayland-sessions,xsessions}
+    #[serde(default)]
+    pub autodiscover: bool,
+    /// Offer a first "Default / Last" entry that logs into the last used session
+    #[serde(default)]
+    pub last_session_entry: bool,
+    /// `PATH` handed to sessions; unset forwards the greeter's own `PATH`,
+    /// and an empty string sends none
+    #[serde(default)]
+    pub path: Option<String>,
 }
 
 /// Keyboard layout configuration
@@ -96,37 +175,254 @@ pub struct UiConfig {
     /// Show clock in the UI
     #[serde(default = "default_true")]
     pub show_clock: bool,
-    /// Clock format (chrono format string)
-    #[serde(default = "default_clock_format")]
-    pub clock_format: String,
+    /// Clock format (chrono format string); overrides `clock_12h` when set
+    #[serde(default)]
+    pub clock_format: Option<String>,
+    /// Use a 12-hour clock with AM/PM
+    #[serde(default)]
+    pub clock_12h: bool,
     /// Show date under clock
     #[serde(default = "default_true")]
     pub show_date: bool,
-    /// Date format (chrono format string)
-    #[serde(default = "default_date_format")]
-    pub date_format: String,
+    /// Date format (chrono format string); overrides `date_style` when set
+    #[serde(default)]
+    pub date_format: Option<String>,
+    /// Named date style: "short", "long" or "iso"
+    #[serde(default = "default_date_style")]
+    pub date_style: String,
+    /// IANA timezone for the clock and date (e.g. "Europe/Berlin"); system local time when unset
+    #[serde(default)]
+    pub timezone: Option<String>,
     /// Color scheme
     #[serde(default)]
     pub colors: ColorScheme,
+    /// pywal/wallust `colors.json` whose palette replaces `colors` when it
+    /// can be read
+    #[serde(default)]
+    pub colors_from: Option<PathBuf>,
+    /// Colors the terminal can show: "auto" (from `$COLORTERM`/`$TERM`),
+    /// "truecolor", "256" or "16". Hex colors are mapped to the nearest
+    /// palette entry below true color.
+    #[serde(default = "default_color_mode")]
+    pub color_mode: String,
 
-    /// Field width as percentage of terminal width
+    /// Field width as percentage of terminal width (100 = full width)
     #[serde(default = "default_field_width")]
     pub field_width: u32,
 
+    /// Upper bound on field width in columns, applied after the percentage
+    #[serde(default)]
+    pub max_field_cols: Option<u32>,
+
+    /// Smallest terminal the form is drawn in; below it a "too small"
+    /// message is shown instead
+    #[serde(default = "default_min_cols")]
+    pub min_cols: u16,
+    #[serde(default = "default_min_rows")]
+    pub min_rows: u16,
+
+    /// Height of each input field in rows, including borders (minimum 3)
+    #[serde(default = "default_field_height")]
+    pub field_height: u32,
+
+    /// Field layout: "stacked" (bordered blocks titled with the label) or
+    /// "compact" (one `Label: input` row per field, for short terminals)
+    #[serde(default = "default_layout_style")]
+    pub layout_style: String,
+
+    /// Field border: "plain", "rounded", "double", "thick" or "none"
+    #[serde(default = "default_border_style")]
+    pub border_style: String,
+
     /// Spacing between input fields (in rows)
     #[serde(default)]
     pub field_spacing: u32,
 
+    /// Show the session selector; when hidden the first session is always used
+    #[serde(default = "default_true")]
+    pub show_session: bool,
+
+    /// Leave the session selector out of the Tab cycle (Alt+1…9 still pick a session)
+    #[serde(default)]
+    pub tab_skips_session: bool,
+
+    /// Tab past the last field returns to the first (and Shift+Tab back);
+    /// when false focus stops at the ends
+    #[serde(default = "default_true")]
+    pub focus_wrap: bool,
+
+    /// Top-to-bottom and Tab order of the input fields, by name from
+    /// [`FIELDS`]. Hidden fields may be listed or left out.
+    #[serde(default = "default_field_order")]
+    pub field_order: Vec<String>,
+
+    /// Label of an extra masked field (e.g. "Keyring passphrase") whose value
+    /// is passed to the session instead of PAM; unset hides the field
+    #[serde(default)]
+    pub extra_secret: Option<String>,
+    /// Session environment variable that receives the extra secret
+    #[serde(default = "default_extra_secret_env")]
+    pub extra_secret_env: String,
+
+    /// Show the selected session's command dimmed under its name
+    #[serde(default)]
+    pub show_session_command: bool,
+
     /// Spacing from top of screen to clock/date (in rows)
     #[serde(default = "default_top_spacing")]
     pub top_spacing: u32,
+    /// Place the first input field this percentage of the screen height from
+    /// the top (e.g. 40), instead of `top_spacing` rows below the title
+    #[serde(default)]
+    pub top_offset_pct: Option<u32>,
     /// Spacing from clock/date to input fields (in rows)
     #[serde(default)]
     pub clock_spacing: u32,
 
+    /// Show "Last login: <time> as <user>" under the clock
+    #[serde(default = "default_true")]
+    pub show_last_login: bool,
+
     /// Title text for the greeter
     #[serde(default = "default_title")]
     pub title: String,
+
+    /// Starting focus when autofill is on: "username", "password" or "smart"
+    /// (password if a username was filled in, otherwise username)
+    #[serde(default = "default_autofill_focus")]
+    pub autofill_focus: String,
+
+    /// Field focused at startup, by name from [`FIELDS`]; overrides
+    /// `autofill_focus`. Must be a shown field.
+    #[serde(default)]
+    pub initial_focus: Option<String>,
+    /// What plain Esc does: "close_overlay" (only closes an open panel),
+    /// "clear_field", "clear_all" or "nothing" (panels then need their F-key)
+    #[serde(default = "default_esc_action")]
+    pub esc_action: String,
+
+    /// Text cursor shape: "default" (terminal's own), "block", "bar" or "underline"
+    #[serde(default = "default_cursor_style")]
+    pub cursor_style: String,
+    /// Whether the text cursor blinks (ignored for "default")
+    #[serde(default = "default_true")]
+    pub cursor_blink: bool,
+
+    /// Offer login users from /etc/passwd on the username field (Up/Down)
+    #[serde(default)]
+    pub user_list: bool,
+    /// If non-empty, only these users are offered
+    #[serde(default)]
+    pub user_list_include: Vec<String>,
+    /// Users never offered
+    #[serde(default)]
+    pub user_list_exclude: Vec<String>,
+
+    /// Fade the focus border in over a few ticks instead of switching instantly
+    #[serde(default)]
+    pub animations: bool,
+
+    /// Dim the title and clock while the password field is focused
+    #[serde(default)]
+    pub focus_dim: bool,
+
+    /// Show the machine's hostname under the title
+    #[serde(default)]
+    pub show_hostname: bool,
+
+    /// Show an initials avatar to the left of the username field
+    #[serde(default)]
+    pub show_avatar: bool,
+
+    /// Note "logging in as <user>" under the password field while the
+    /// username is the autofilled one
+    #[serde(default = "default_true")]
+    pub show_autofill_hint: bool,
+
+    /// Append the number of typed characters to the masked password's title
+    #[serde(default)]
+    pub show_password_length: bool,
+
+    /// Allow F12 to toggle a preview of the argv/env sent to greetd
+    #[serde(default)]
+    pub debug_overlay: bool,
+
+    /// Allow F9 to open a read-only panel with host, distro, kernel, uptime,
+    /// memory and versions
+    #[serde(default)]
+    pub system_info: bool,
+
+    /// Let Ctrl+Up/Down change the backlight via /sys/class/backlight
+    #[serde(default)]
+    pub allow_brightness: bool,
+
+    /// Seconds a login may take before the progress line says the auth
+    /// server is being contacted
+    #[serde(default = "default_slow_auth_secs")]
+    pub slow_auth_secs: u64,
+
+    /// Message shown above the help bar (e.g. support contact or room number)
+    #[serde(default)]
+    pub footer_message: Option<String>,
+    /// File (legal notice, message of the day) shown wrapped next to the
+    /// fields; re-read when it changes
+    #[serde(default)]
+    pub banner_file: Option<PathBuf>,
+    /// Where the banner goes: "above" or "below" the fields
+    #[serde(default = "default_banner_position")]
+    pub banner_position: String,
+
+    /// Status items in the screen corners
+    #[serde(default)]
+    pub widgets: Vec<WidgetConfig>,
+
+    /// Show the key help bar
+    #[serde(default = "default_true")]
+    pub show_help: bool,
+    /// Row for the help bar: "top" or "bottom"
+    #[serde(default = "default_help_position")]
+    pub help_position: String,
+
+    /// Milliseconds to show "Welcome, <user>" after a successful login (0 = exit at once)
+    #[serde(default)]
+    pub success_delay_ms: u64,
+
+    /// Seconds before an error message dismisses itself (0 = until a key press)
+    #[serde(default)]
+    pub error_timeout_secs: u64,
+
+    /// Ring the terminal bell when a login fails
+    #[serde(default)]
+    pub bell_on_error: bool,
+    /// Command run (detached, time-limited) when a login fails, e.g. to play a sound
+    #[serde(default)]
+    pub error_sound_cmd: Option<String>,
+    /// Ring the terminal bell when a login succeeds
+    #[serde(default)]
+    pub bell_on_success: bool,
+    /// Command run (detached, time-limited) when a login succeeds
+    #[serde(default)]
+    pub success_sound_cmd: Option<String>,
+}
+
+/// A status item in a screen corner. The first of `text`, `command` and
+/// `builtin` that is set is shown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WidgetConfig {
+    /// "tl", "tr", "bl" or "br"
+    pub corner: String,
+    /// Fixed text
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Command whose first output line is shown
+    #[serde(default)]
+    pub command: Option<String>,
+    /// "clock", "hostname", "battery" or "uptime"
+    #[serde(default)]
+    pub builtin: Option<String>,
+    /// Seconds between runs of `command`
+    #[serde(default = "default_widget_refresh_secs")]
+    pub refresh_secs: u64,
 }
 
 /// Color configuration
@@ -140,6 +436,37 @@ pub struct ColorScheme {
     pub focused: String,
     #[serde(default = "default_accent")]
     pub error: String,
+    #[serde(default = "default_success")]
+    pub success: String,
+    /// Clock color; unset keeps the bold white clock
+    #[serde(default)]
+    pub clock: Option<String>,
+    /// Date color; unset keeps the gray date
+    #[serde(default)]
+    pub date: Option<String>,
+    /// One color for `focused`, `title` and `selection` wherever those are
+    /// not set themselves
+    #[serde(default)]
+    pub accent: Option<String>,
+    /// Title color; unset uses `accent`, else cyan
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Highlight of a selected (replace-on-type) password; unset uses
+    /// `accent`, else reverse video
+    #[serde(default)]
+    pub selection: Option<String>,
+}
+
+impl ColorScheme {
+    /// The title color: `title`, else `accent`
+    pub fn title_color(&self) -> Option<&str> {
+        self.title.as_deref().or(self.accent.as_deref())
+    }
+
+    /// The selection highlight: `selection`, else `accent`
+    pub fn selection_color(&self) -> Option<&str> {
+        self.selection.as_deref().or(self.accent.as_deref())
+    }
 }
 
 /// Security-related configuration
@@ -148,27 +475,137 @@ pub struct SecurityConfig {
     /// Clear password field after failed attempt
     #[serde(default = "default_true")]
     pub clear_password_on_error: bool,
+    /// What a failed login does to the password: "clear", "keep" or "select"
+    /// (kept but replaced by the next keystroke). Unset follows
+    /// `clear_password_on_error`.
+    #[serde(default)]
+    pub on_error_password: Option<String>,
     /// Show asterisks for password
     #[serde(default = "default_true")]
     pub mask_password: bool,
+    /// Character shown per masked password character, e.g. "•"; anything
+    /// but a single one-column character falls back to "*"
+    #[serde(default)]
+    pub mask_char: Option<String>,
+    /// Briefly show the last typed password character before masking it
+    #[serde(default)]
+    pub reveal_last_char: bool,
+    /// Make `default_user` the only username: the field is skipped and read-only
+    #[serde(default)]
+    pub lock_default_user: bool,
+    /// Labels of the prompts PAM asks after the password (e.g. an OTP), each
+    /// shown as its own field and answered in order on Enter
+    #[serde(default)]
+    pub prompt_form: Vec<String>,
+    /// Let Enter submit an empty password (for passwordless PAM setups)
+    #[serde(default)]
+    pub allow_empty_password: bool,
+    /// Offer a login shell when no session is configured or installed
+    #[serde(default)]
+    pub allow_fallback_shell: bool,
+    /// Strip surrounding whitespace (e.g. from a paste) from the username
+    #[serde(default = "default_true")]
+    pub trim_username: bool,
+    /// Longest username that can be typed (0 = unlimited)
+    #[serde(default)]
+    pub max_username_len: usize,
+    /// Characters allowed in usernames, as a bracket-less class such as
+    /// "a-z0-9_-" (unset = anything)
+    #[serde(default)]
+    pub username_charset: Option<String>,
+    /// Deterrent mode: blank the whole screen after this many failed logins
+    /// (0 = off) until `blank_unlock_sequence` is typed
+    #[serde(default)]
+    pub blank_after_failures: u32,
+    /// Text an admin types on the blank screen to restore the login form;
+    /// blanking stays off while this is unset or empty
+    #[serde(default)]
+    pub blank_unlock_sequence: Option<String>,
+    /// Seconds without a key press before `idle_action` runs (0 = never)
+    #[serde(default)]
+    pub input_timeout: u64,
+    /// What an idle timeout does: "clear" (the secrets and messages), "exit"
+    /// (greetd restarts the greeter), "suspend" (`power.suspend_command`) or
+    /// "screensaver" (blank until a key is pressed)
+    #[serde(default = "default_idle_action")]
+    pub idle_action: String,
+}
+
+/// Power action configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerConfig {
+    /// Command run on F1
+    #[serde(default = "default_poweroff_command")]
+    pub poweroff_command: String,
+    /// Command run on F2
+    #[serde(default = "default_reboot_command")]
+    pub reboot_command: String,
+    /// Command run by `security.idle_action = "suspend"`
+    #[serde(default = "default_suspend_command")]
+    pub suspend_command: String,
+    /// Require a second F1 press before powering off
+    #[serde(default = "default_true")]
+    pub confirm_poweroff: bool,
+    /// Require a second F2 press before rebooting
+    #[serde(default = "default_true")]
+    pub confirm_reboot: bool,
+    /// Seconds the second press is accepted for
+    #[serde(default = "default_confirm_timeout_secs")]
+    pub confirm_timeout_secs: u64,
+}
+
+/// Commands run on login events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Command run after each failed authentication
+    #[serde(default)]
+    pub on_failure: Option<String>,
+    /// Minimum seconds between two `on_failure` runs
+    #[serde(default = "default_hook_interval_secs")]
+    pub on_failure_interval_secs: u64,
+    /// Broadcast a `org.hyprgreeter.Greeter.LoggedIn` signal on the system
+    /// bus after each successful login
+    #[serde(default)]
+    pub dbus_notify: bool,
 }
 
 // Default value helpers
 fn default_true() -> bool { true }
-fn default_clock_format() -> String { "%H:%M".to_string() }
-fn default_date_format() -> String { "%A, %d %B %Y".to_string() }
+fn default_date_style() -> String { "long".to_string() }
+fn default_field_order() -> Vec<String> { FIELDS.iter().map(|f| f.to_string()).collect() }
 fn default_field_width() -> u32 { 50 }
+fn default_field_height() -> u32 { 3 }
+fn default_border_style() -> String { "plain".to_string() }
+fn default_autofill_focus() -> String { "smart".to_string() }
+fn default_cursor_style() -> String { "default".to_string() }
+fn default_layout_style() -> String { "stacked".to_string() }
+fn default_help_position() -> String { "bottom".to_string() }
+fn default_extra_secret_env() -> String { "GREETER_EXTRA_SECRET".to_string() }
 fn default_top_spacing() -> u32 { 15 }
+fn default_banner_position() -> String { "above".to_string() }
+fn default_esc_action() -> String { "close_overlay".to_string() }
 fn default_title() -> String { "hypr-greeter".to_string() }
 fn default_bg() -> String { "#1a1b26".to_string() }
 fn default_fg() -> String { "#c0caf5".to_string() }
 fn default_accent() -> String { "#f7768e".to_string() }
+fn default_success() -> String { "#9ece6a".to_string() }
+fn default_poweroff_command() -> String { "systemctl poweroff".to_string() }
+fn default_reboot_command() -> String { "systemctl reboot".to_string() }
+fn default_suspend_command() -> String { "systemctl suspend".to_string() }
+fn default_idle_action() -> String { "clear".to_string() }
+fn default_widget_refresh_secs() -> u64 { 30 }
+fn default_color_mode() -> String { "auto".to_string() }
+fn default_slow_auth_secs() -> u64 { 3 }
+fn default_min_cols() -> u16 { 40 }
+fn default_min_rows() -> u16 { 12 }
+fn default_confirm_timeout_secs() -> u64 { 3 }
+fn default_hook_interval_secs() -> u64 { 10 }
 
 fn default_sessions() -> Vec<Session> {
     vec![
-        Session { name: "Hyprland".to_string(), command: "start-hyprland".to_string() },
-        Session { name: "Sway".to_string(), command: "sway".to_string() },
-        Session { name: "TTY".to_string(), command: "/bin/bash".to_string() },
+        Session::new("Hyprland", "start-hyprland"),
+        Session::new("Sway", "sway"),
+        Session::new("TTY", "/bin/bash"),
     ]
 }
 
@@ -176,13 +613,35 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             last_user: None,
+            last_login: None,
+            last_session: None,
+            load_error: None,
+            state_loaded: false,
+            once: false,
             default_user: None,
             disable_autofill: None,
+            greetd_socket: None,
+            session_env: Vec::new(),
             monitors: Vec::new(),
             sessions: default_sessions(),
+            session: SessionOptions::default(),
             input: InputConfig::default(),
             ui: UiConfig::default(),
             security: SecurityConfig::default(),
+            power: PowerConfig::default(),
+            hooks: HooksConfig::default(),
+        }
+    }
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            strict_env: false,
+            load_environment_files: true,
+            autodiscover: false,
+            last_session_entry: false,
+            path: None,
         }
     }
 }
@@ -191,15 +650,140 @@ impl Default for UiConfig {
     fn default() -> Self {
         Self {
             show_clock: true,
-            clock_format: default_clock_format(),
+            clock_format: None,
+            clock_12h: false,
             show_date: true,
-            date_format: default_date_format(),
+            date_format: None,
+            date_style: default_date_style(),
+            timezone: None,
             colors: ColorScheme::default(),
+            colors_from: None,
+            color_mode: default_color_mode(),
             field_width: default_field_width(),
+            max_field_cols: None,
+            min_cols: default_min_cols(),
+            min_rows: default_min_rows(),
+            field_height: default_field_height(),
+            layout_style: default_layout_style(),
+            border_style: default_border_style(),
             field_spacing: 0,
+            show_session: true,
+            tab_skips_session: false,
+            focus_wrap: true,
+            field_order: default_field_order(),
+            extra_secret: None,
+            extra_secret_env: default_extra_secret_env(),
+            show_session_command: false,
             top_spacing: default_top_spacing(),
+            top_offset_pct: None,
             clock_spacing: 0,
+            show_last_login: true,
             title: default_title(),
+            autofill_focus: default_autofill_focus(),
+            initial_focus: None,
+            esc_action: default_esc_action(),
+            cursor_style: default_cursor_style(),
+            cursor_blink: true,
+            user_list: false,
+            user_list_include: Vec::new(),
+            user_list_exclude: Vec::new(),
+            animations: false,
+            focus_dim: false,
+            show_hostname: false,
+            show_avatar: false,
+            show_autofill_hint: true,
+            show_password_length: false,
+            debug_overlay: false,
+            system_info: false,
+            allow_brightness: false,
+            slow_auth_secs: default_slow_auth_secs(),
+            footer_message: None,
+            banner_file: None,
+            banner_position: default_banner_position(),
+            widgets: Vec::new(),
+            show_help: true,
+            help_position: default_help_position(),
+            success_delay_ms: 0,
+            error_timeout_secs: 0,
+            bell_on_error: false,
+            error_sound_cmd: None,
+            bell_on_success: false,
+            success_sound_cmd: None,
+        }
+    }
+}
+
+impl UiConfig {
+    /// Names of the shown input fields in `field_order`. Shown fields the
+    /// order leaves out are appended in the default order, so the password
+    /// can never disappear.
+    pub fn visible_fields(&self) -> Vec<&'static str> {
+        let shown = |name: &&str| match *name {
+            "extra_secret" => self.extra_secret.is_some(),
+            "session" => self.show_session,
+            _ => true,
+        };
+        let listed = self.field_order.iter().filter_map(|name| FIELDS.into_iter().find(|f| f == name));
+        let mut fields = Vec::new();
+        for name in listed.chain(FIELDS).filter(shown) {
+            if !fields.contains(&name) {
+                fields.push(name);
+            }
+        }
+        fields
+    }
+
+    /// Check that `field_order` names known fields, each at most once, and
+    /// includes every shown one
+    pub fn check_field_order(&self) -> Result<(), String> {
+        for (i, name) in self.field_order.iter().enumerate() {
+            if !FIELDS.contains(&name.as_str()) {
+                return Err(format!("ui.field_order: unknown field {:?}", name));
+            }
+            if self.field_order[..i].contains(name) {
+                return Err(format!("ui.field_order: {:?} is listed twice", name));
+            }
+        }
+        match self.visible_fields().into_iter().find(|f| !self.field_order.iter().any(|n| n == f)) {
+            Some(missing) => Err(format!("ui.field_order: missing shown field {:?}", missing)),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that `initial_focus`, when set, names a shown field
+    pub fn check_initial_focus(&self) -> Result<(), String> {
+        match self.initial_focus.as_deref() {
+            Some(name) if !self.visible_fields().contains(&name) => {
+                Err(format!("ui.initial_focus: {:?} is not a shown field", name))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether plain Esc closes the system info and launch preview panels
+    pub fn esc_closes_overlay(&self) -> bool {
+        self.esc_action != "nothing"
+    }
+
+    /// Chrono format for the clock: `clock_format` if set, else 24h or 12h
+    pub fn effective_clock_format(&self) -> &str {
+        match self.clock_format.as_deref() {
+            Some(fmt) => fmt,
+            None if self.clock_12h => "%I:%M %p",
+            None => "%H:%M",
+        }
+    }
+
+    /// Chrono format for the date: `date_format` if set, else the named
+    /// `date_style` ("long" for unknown styles)
+    pub fn effective_date_format(&self) -> &str {
+        match self.date_format.as_deref() {
+            Some(fmt) => fmt,
+            None => match self.date_style.as_str() {
+                "short" => "%a, %d %b",
+                "iso" => "%Y-%m-%d",
+                _ => "%A, %d %B %Y",
+            },
         }
     }
 }
@@ -211,6 +795,12 @@ impl Default for ColorScheme {
             foreground: default_fg(),
             focused: default_accent(),
             error: default_accent(),
+            success: default_success(),
+            clock: None,
+            date: None,
+            accent: None,
+            title: None,
+            selection: None,
         }
     }
 }
@@ -219,24 +809,202 @@ impl Default for SecurityConfig {
     fn default() -> Self {
         Self {
             clear_password_on_error: true,
+            on_error_password: None,
             mask_password: true,
+            mask_char: None,
+            reveal_last_char: false,
+            lock_default_user: false,
+            prompt_form: Vec::new(),
+            allow_empty_password: false,
+            allow_fallback_shell: false,
+            trim_username: true,
+            max_username_len: 0,
+            username_charset: None,
+            blank_after_failures: 0,
+            blank_unlock_sequence: None,
+            input_timeout: 0,
+            idle_action: default_idle_action(),
         }
     }
 }
 
-/// Get the configuration file path
-pub fn config_path() -> PathBuf {
-    // Try system config first
-    let system_config = PathBuf::from("/etc/hypr-greeter/config.toml");
-    if system_config.exists() {
-        return system_config;
+impl Config {
+    /// Check that every option taking one of a fixed set of words names one
+    /// of them, so a typo is a config error rather than a silent default
+    pub fn check_choices(&self) -> Result<(), String> {
+        let (ui, security) = (&self.ui, &self.security);
+        let mut choices: Vec<(String, Option<String>, &[&str])> = vec![
+            ("ui.date_style".into(), Some(ui.date_style.clone()), &["short", "long", "iso"]),
+            ("ui.color_mode".into(), Some(ui.color_mode.clone()), &["auto", "truecolor", "256", "16"]),
+            ("ui.layout_style".into(), Some(ui.layout_style.clone()), &["stacked", "compact"]),
+            ("ui.border_style".into(), Some(ui.border_style.clone()), &["plain", "rounded", "double", "thick", "none"]),
+            ("ui.autofill_focus".into(), Some(ui.autofill_focus.clone()), &["smart", "username", "password"]),
+            ("ui.esc_action".into(), Some(ui.esc_action.clone()), &["close_overlay", "clear_field", "clear_all", "nothing"]),
+            ("ui.cursor_style".into(), Some(ui.cursor_style.clone()), &["default", "block", "bar", "underline"]),
+            ("ui.banner_position".into(), Some(ui.banner_position.clone()), &["above", "below"]),
+            ("ui.help_position".into(), Some(ui.help_position.clone()), &["top", "bottom"]),
+            ("security.on_error_password".into(), security.on_error_password.clone(), &["clear", "keep", "select"]),
+            ("security.idle_action".into(), Some(security.idle_action.clone()), &["clear", "exit", "suspend", "screensaver"]),
+        ];
+        for (i, widget) in ui.widgets.iter().enumerate() {
+            // Corners are matched case-insensitively when drawing
+            choices.push((format!("ui.widgets[{}].corner", i), Some(widget.corner.to_lowercase()), &["tl", "tr", "bl", "br"]));
+            choices.push((format!("ui.widgets[{}].builtin", i), widget.builtin.clone(), &["clock", "hostname", "battery", "uptime"]));
+        }
+        for (key, value, allowed) in choices {
+            match value {
+                Some(value) if !allowed.contains(&value.as_str()) => {
+                    return Err(format!("{}: {:?} is not one of {}", key, value, allowed.join(", ")));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// The username when `security.lock_default_user` pins it to `default_user`
+    pub fn locked_user(&self) -> Option<&str> {
+        self.default_user
+            .as_deref()
+            .filter(|user| self.security.lock_default_user && !user.is_empty())
+    }
+}
+
+impl SecurityConfig {
+    /// `on_error_password`, or the mode the older `clear_password_on_error`
+    /// boolean stands for
+    pub fn password_on_error(&self) -> &str {
+        match self.on_error_password.as_deref() {
+            Some(mode) => mode,
+            None if self.clear_password_on_error => "clear",
+            None => "keep",
+        }
+    }
+
+    /// The password mask: `mask_char` when it is one character exactly one
+    /// column wide, else "*"
+    pub fn mask(&self) -> &str {
+        match self.mask_char.as_deref() {
+            Some(mask) if mask.chars().count() == 1 && UnicodeWidthStr::width(mask) == 1 => mask,
+            _ => "*",
+        }
+    }
+
+    /// Whether `c` matches `username_charset`. Ranges are written `a-z`; a
+    /// `-` at either end of the class is literal.
+    pub fn username_char_allowed(&self, c: char) -> bool {
+        let Some(class) = self.username_charset.as_deref() else {
+            return true;
+        };
+        let chars: Vec<char> = class.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            if i + 2 < chars.len() && chars[i + 1] == '-' {
+                if (chars[i]..=chars[i + 2]).contains(&c) {
+                    return true;
+                }
+                i += 3;
+            } else {
+                if chars[i] == c {
+                    return true;
+                }
+                i += 1;
+            }
+        }
+        false
+    }
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            poweroff_command: default_poweroff_command(),
+            reboot_command: default_reboot_command(),
+            suspend_command: default_suspend_command(),
+            confirm_poweroff: true,
+            confirm_reboot: true,
+            confirm_timeout_secs: default_confirm_timeout_secs(),
+        }
+    }
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            on_failure: None,
+            on_failure_interval_secs: default_hook_interval_secs(),
+            dbus_notify: false,
+        }
+    }
+}
+
+/// JSON Schema for the config file, printed by `--print-schema`
+pub const CONFIG_SCHEMA: &str = include_str!("../config.schema.json");
+
+/// Environment variable naming a final, highest-precedence config file
+pub const CONFIG_ENV: &str = "HYPR_GREETER_CONFIG";
+
+/// Environment variable naming a session (by name or command) to preselect
+pub const SESSION_ENV: &str = "HYPR_GREETER_SESSION";
+
+/// Greeter state (last user, session and login time), kept across restarts
+pub const STATE_FILE: &str = "/var/lib/greetd/last_user.json";
+
+/// Input fields known to `ui.field_order`, in their default order
+pub const FIELDS: [&str; 4] = ["username", "password", "extra_secret", "session"];
+
+/// Config files in increasing order of precedence. Each one that exists is
+/// merged over the ones before it, on top of the built-in defaults:
+/// `/etc/hypr-greeter/config.toml`, then `$XDG_CONFIG_HOME/hypr-greeter/config.toml`,
+/// then `$HYPR_GREETER_CONFIG`.
+pub fn config_layers() -> Vec<PathBuf> {
+    let mut layers = vec![PathBuf::from("/etc/hypr-greeter/config.toml")];
+    if let Some(dir) = dirs::config_dir() {
+        layers.push(dir.join("hypr-greeter").join("config.toml"));
     }
+    if let Some(path) = std::env::var_os(CONFIG_ENV).filter(|p| !p.is_empty()) {
+        layers.push(PathBuf::from(path));
+    }
+    layers
+}
+
+/// Get the highest-precedence configuration file that exists, or the user
+/// config path if none do
+pub fn config_path() -> PathBuf {
+    config_layers()
+        .into_iter()
+        .rev()
+        .find(|p| p.exists())
+        .unwrap_or_else(|| {
+            dirs::config_dir()
+                .unwrap_or_default()
+                .join("hypr-greeter")
+                .join("config.toml")
+        })
+}
 
-    // Fall back to user config
-    dirs::config_dir()
-        .unwrap_or_default()
-        .join("hypr-greeter")
-        .join("config.toml")
+/// `--verbose`: which config files were found, which one `config_path`
+/// resolved to, and whether the state file was merged in
+pub fn config_report(config: &Config) -> String {
+    let mut out = String::from("config layers (later wins):\n");
+    for layer in config_layers() {
+        let status = if layer.exists() { "found" } else { "missing" };
+        out.push_str(&format!("  {} ({})\n", layer.display(), status));
+    }
+    out.push_str(&format!("config path: {}\n", config_path().display()));
+    if let Some(ref e) = config.load_error {
+        out.push_str(&format!("config error, using defaults: {}\n", e));
+    }
+    if config.state_loaded {
+        out.push_str(&format!(
+            "state file {}: merged (last user {:?})\n",
+            STATE_FILE,
+            config.last_user.as_deref().unwrap_or("")
+        ));
+    } else {
+        out.push_str(&format!("state file {}: not merged (missing or unreadable)\n", STATE_FILE));
+    }
+    out
 }
 
 /// Load configuration from disk using the default search path.
@@ -244,33 +1012,143 @@ pub fn load_config() -> Result<Config, Box<dyn Error>> {
     load_config_from(None)
 }
 
-/// Load configuration from an explicit path, or fall back to the default search path.
+/// Load configuration from an explicit path, or merge the default layers.
 pub fn load_config_from(path: Option<&Path>) -> Result<Config, Box<dyn Error>> {
-    let cfg_path = match path {
-        Some(p) => p.to_path_buf(),
-        None => config_path(),
-    };
-    let mut config: Config = match std::fs::read_to_string(&cfg_path) {
-        Ok(content) => toml::from_str(&content)?,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Config::default(),
-        Err(e) => return Err(e.into()),
+    let mut config = match path {
+        Some(p) => load_layers(&[p.to_path_buf()])?,
+        None => load_layers(&config_layers())?,
     };
+    load_state(&mut config);
+    Ok(config)
+}
 
-    if let Ok(content) = std::fs::read_to_string("/var/lib/greetd/last_user.json") {
+/// Load the configuration, falling back to the built-in defaults (with
+/// `load_error` set) when the files are broken, so a typo can never make
+/// logging in impossible.
+pub fn load_config_or_default() -> Config {
+    load_config().unwrap_or_else(|e| {
+        let mut config = Config { load_error: Some(e.to_string()), ..Config::default() };
+        load_state(&mut config);
+        config
+    })
+}
+
+/// Fill in `last_user`, `last_login` and `last_session` from the greeter's state file
+fn load_state(config: &mut Config) {
+    if let Ok(content) = std::fs::read_to_string(STATE_FILE) {
         if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+            config.state_loaded = true;
             if let Some(last_user) = json.get("last_user").and_then(|v| v.as_str()) {
                 config.last_user = Some(last_user.to_string());
             }
+            config.last_session = json.get("last_session").and_then(|v| v.as_str()).map(str::to_string);
+            // Older state files have no timestamp; an unparsable one is ignored
+            config.last_login = json
+                .get("last_login")
+                .and_then(|v| v.as_str())
+                .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                .map(|t| t.with_timezone(&Local));
         }
     }
+}
 
+/// Merge the given config files over the built-in defaults. Tables are
+/// merged key by key, so a later file only needs the fields it changes;
+/// any other value (including arrays like `sessions`) replaces the earlier
+/// one wholesale. Missing files are skipped.
+pub fn load_layers(paths: &[PathBuf]) -> Result<Config, Box<dyn Error>> {
+    let mut merged = toml::Value::Table(toml::Table::new());
+    for path in paths {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("{}: {}", path.display(), e).into()),
+        };
+        let layer: toml::Table = content
+            .parse()
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        merge_toml(&mut merged, toml::Value::Table(layer));
+    }
+    // `ui.colors.accent` stands in for `focused` unless a layer set it
+    let focused_set = merged
+        .get("ui")
+        .and_then(|ui| ui.get("colors"))
+        .is_some_and(|colors| colors.get("focused").is_some());
+    let mut config: Config = merged.try_into()?;
+    config.ui.check_field_order()?;
+    config.ui.check_initial_focus()?;
+    config.check_choices()?;
+    if let Some(path) = config.ui.colors_from.clone() {
+        // A missing or broken palette keeps the configured colors
+        if let Err(e) = load_wal_colors(&path, &mut config.ui.colors) {
+            eprintln!("hypr-greeter: ignoring ui.colors_from: {}", e);
+        }
+    }
+    // After the palette, so an explicit accent beats its color4
+    if let Some(accent) = config.ui.colors.accent.clone().filter(|_| !focused_set) {
+        config.ui.colors.focused = accent;
+    }
     Ok(config)
 }
 
-/// Save the last logged in username to a file
-pub fn save_last_user(username: &str) -> Result<(), Box<dyn Error>> {
-    let user_file = PathBuf::from("/var/lib/greetd/last_user.json");
-    let content = serde_json::to_string_pretty(&serde_json::json!({"last_user": username}))?;
+/// Replace `colors` with the palette of a pywal/wallust `colors.json`:
+/// background and foreground from `special`, and color4 (blue), color1 (red)
+/// and color2 (green) for focused, error and success. A leading `~/` is the
+/// home directory.
+pub fn load_wal_colors(path: &Path, colors: &mut ColorScheme) -> Result<(), String> {
+    let path = match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    };
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let json: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let get = |section: &str, key: &str| {
+        json[section][key]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("{}: no {}.{}", path.display(), section, key))
+    };
+    *colors = ColorScheme {
+        background: get("special", "background")?,
+        foreground: get("special", "foreground")?,
+        focused: get("colors", "color4")?,
+        error: get("colors", "color1")?,
+        success: get("colors", "color2")?,
+        // The palette has no clock/date roles; keep what [ui.colors] set
+        clock: colors.clock.take(),
+        date: colors.date.take(),
+        accent: colors.accent.take(),
+        title: colors.title.take(),
+        selection: colors.selection.take(),
+    };
+    Ok(())
+}
+
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Save the last logged in username, session name and the login time to a file
+pub fn save_last_user(username: &str, session: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let user_file = PathBuf::from(STATE_FILE);
+    let content = serde_json::to_string_pretty(&serde_json::json!({
+        "last_user": username,
+        "last_session": session,
+        "last_login": Local::now().to_rfc3339(),
+    }))?;
     if let Some(parent) = user_file.parent() {
         std::fs::create_dir_all(parent)?;
     }
@@ -0,0 +1,123 @@
+//! `--doctor`: check the pieces a working greeter depends on and print a
+//! checklist, so setup problems can be found without starting the TUI.
+
+use crate::config::{self, Config};
+use crate::greetd_client::{self, GreetdClient};
+use crate::session;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::time::Duration;
+
+/// How long the greetd connection check may take
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Outcome of one check: what was looked at, and whether it is fine
+pub struct Check {
+    pub name: String,
+    pub result: Result<String, String>,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, result: Result<String, String>) -> Self {
+        Self { name: name.into(), result }
+    }
+}
+
+/// Run every check. Later checks use the loaded config, or the built-in
+/// defaults when it does not load.
+pub fn checks() -> Vec<Check> {
+    let mut checks = Vec::new();
+    let config = match config::load_config() {
+        Ok(config) => {
+            checks.push(Check::new("config", Ok("loads and validates".to_string())));
+            config
+        }
+        Err(e) => {
+            checks.push(Check::new("config", Err(e.to_string())));
+            Config::default()
+        }
+    };
+
+    let target = greetd_client::socket_target(config.greetd_socket.as_deref());
+    checks.push(Check::new(format!("greetd at {}", target), check_greetd(&target)));
+    checks.extend(check_sessions(&config));
+    checks.push(Check::new("state directory", check_state_dir()));
+    checks.push(Check::new("terminal", check_terminal()));
+    checks
+}
+
+/// Print the checklist; returns whether everything passed
+pub fn run() -> bool {
+    let checks = checks();
+    let color = std::io::stdout().is_terminal();
+    for check in &checks {
+        let (mark, ansi, detail) = match &check.result {
+            Ok(detail) => ("ok", "32", detail),
+            Err(detail) => ("FAIL", "31", detail),
+        };
+        if color {
+            println!("\x1b[{}m[{:>4}]\x1b[0m {}: {}", ansi, mark, check.name, detail);
+        } else {
+            println!("[{:>4}] {}: {}", mark, check.name, detail);
+        }
+    }
+    checks.iter().all(|c| c.result.is_ok())
+}
+
+fn check_greetd(target: &str) -> Result<String, String> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| e.to_string())?;
+    runtime.block_on(async {
+        match tokio::time::timeout(CONNECT_TIMEOUT, GreetdClient::connect_to(target)).await {
+            Ok(Ok(_)) => Ok(format!("reachable, greetd-ipc {}", greetd_client::GREETD_IPC_VERSION)),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err("connection timed out".to_string()),
+        }
+    })
+}
+
+/// Each session's program must be found the way greetd will look for it
+fn check_sessions(config: &Config) -> Vec<Check> {
+    let path = std::env::var("PATH").unwrap_or_default();
+    let (sessions, errors) =
+        session::effective_sessions(&config.sessions, &config.session, &session::drop_in_dirs());
+    let mut checks: Vec<Check> = errors.into_iter().map(|e| Check::new("drop-in", Err(e))).collect();
+    if sessions.is_empty() {
+        checks.push(Check::new("sessions", Err("none configured".to_string())));
+        return checks;
+    }
+    checks.extend(sessions
+        .iter()
+        .filter(|s| s.command != session::LAST_SESSION)
+        .map(|s| {
+            let result = session::build_argv(s, &config.session).and_then(|argv| {
+                let program = argv.first().ok_or("empty command")?;
+                session::find_program(program, &path)
+                    .map(|found| found.display().to_string())
+                    .ok_or_else(|| format!("{} not found on PATH", program))
+            });
+            Check::new(format!("session {}", s.name), result)
+        }));
+    checks
+}
+
+/// `last_user` is saved next to greetd's state; the directory must be writable
+fn check_state_dir() -> Result<String, String> {
+    let dir = Path::new(config::STATE_FILE).parent().ok_or("no state directory")?;
+    let probe = dir.join(".hypr-greeter-doctor");
+    std::fs::write(&probe, b"")
+        .and_then(|_| std::fs::remove_file(&probe))
+        .map(|_| format!("{} is writable", dir.display()))
+        .map_err(|e| format!("{}: {}", dir.display(), e))
+}
+
+fn check_terminal() -> Result<String, String> {
+    if !std::io::stdout().is_terminal() {
+        return Err("stdout is not a terminal".to_string());
+    }
+    let term = std::env::var("TERM").map_err(|_| "TERM is not set".to_string())?;
+    let (cols, rows) = crossterm::terminal::size().map_err(|e| e.to_string())?;
+    Ok(format!("{} ({}x{})", term, cols, rows))
+}
@@ -1,13 +1,39 @@
-use greetd_ipc::{AuthMessageType, Request, Response};
+use greetd_ipc::{AuthMessageType, ErrorType, Request, Response};
 use std::fmt;
+use std::io;
 use tokio::net::UnixStream;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Version of the greetd-ipc protocol crate this client is built against
+pub const GREETD_IPC_VERSION: &str = "0.10";
+
+/// What this client assumes about the daemon. greetd has no version or
+/// capabilities message, so nothing is negotiated; these are the wire-format
+/// and request assumptions baked in at build time, quoted in bug reports.
+pub const PROTOCOL_ASSUMPTIONS: &str =
+    "JSON with native-endian u32 length prefix; create_session/post_auth_message_response/start_session/cancel_session";
+
+/// One-line protocol description for logs, the debug overlay and errors
+pub fn protocol_summary(target: &str) -> String {
+    format!("greetd-ipc {} at {} ({})", GREETD_IPC_VERSION, target, PROTOCOL_ASSUMPTIONS)
+}
+
+/// Socket used when neither the config nor `GREETD_SOCK` names one
+pub const DEFAULT_SOCKET: &str = "/run/greetd.sock";
+
+/// Byte stream to greetd; the framing is identical over every transport
+trait IpcStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> IpcStream for T {}
 
 /// Typed error for greetd operations
 #[derive(Debug)]
 pub enum GreetdError {
     ConnectionFailed(String),
+    /// PAM rejected the credentials
     AuthFailed(String),
+    /// greetd or PAM failed for reasons other than the credentials
+    System(String),
     SessionFailed(String),
     Protocol(String),
 }
@@ -17,32 +43,80 @@ impl fmt::Display for GreetdError {
         match self {
             GreetdError::ConnectionFailed(msg) => write!(f, "Connection failed: {}", msg),
             GreetdError::AuthFailed(msg) => write!(f, "Authentication failed: {}", msg),
+            GreetdError::System(msg) => write!(f, "System error: {}", msg),
             GreetdError::SessionFailed(msg) => write!(f, "Session failed: {}", msg),
-            GreetdError::Protocol(msg) => write!(f, "Protocol error: {}", msg),
+            GreetdError::Protocol(msg) => {
+                write!(f, "Protocol error: {} (greetd-ipc {})", msg, GREETD_IPC_VERSION)
+            }
         }
     }
 }
 
 impl std::error::Error for GreetdError {}
 
+impl GreetdError {
+    /// The error for greetd's `Error` reply: an `AuthError` is a rejected
+    /// login, anything else a failure that is not the user's fault
+    pub fn from_reply(error_type: ErrorType, description: String) -> Self {
+        match error_type {
+            ErrorType::AuthError => GreetdError::AuthFailed(description),
+            ErrorType::Error => GreetdError::System(description),
+        }
+    }
+
+    /// Whether greetd refused a new conversation because another one is
+    /// still being configured. greetd reports this as a plain `Error` reply
+    /// with the fixed description [`SESSION_IN_PROGRESS`]; the protocol has
+    /// no dedicated error type for it, so the text is compared exactly.
+    pub fn is_session_in_progress(&self) -> bool {
+        matches!(self, GreetdError::System(msg) if msg == SESSION_IN_PROGRESS)
+    }
+}
+
+/// greetd's description when `create_session` finds a conversation open
+pub const SESSION_IN_PROGRESS: &str = "a session is already being configured";
+
 /// Result type for greetd operations
 pub type GreetdResult<T> = Result<T, GreetdError>;
 
 /// greetd client for authentication
 pub struct GreetdClient {
-    stream: UnixStream,
+    stream: Box<dyn IpcStream>,
+    /// Set when `create_session` had to cancel a stale conversation
+    cancelled_stale: bool,
+}
+
+/// Where to reach greetd: `configured` if set, else `GREETD_SOCK`, else
+/// [`DEFAULT_SOCKET`]
+pub fn socket_target(configured: Option<&str>) -> String {
+    configured
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .or_else(|| std::env::var("GREETD_SOCK").ok())
+        .unwrap_or_else(|| DEFAULT_SOCKET.to_string())
 }
 
 impl GreetdClient {
-    /// Connect to greetd daemon
+    /// Connect to greetd daemon at `GREETD_SOCK`
     pub async fn connect() -> GreetdResult<Self> {
-        let socket_path = std::env::var("GREETD_SOCK")
-            .unwrap_or_else(|_| "/run/greetd.sock".to_string());
+        Self::connect_to(&socket_target(None)).await
+    }
 
-        let stream = UnixStream::connect(&socket_path).await.map_err(|e| {
-            GreetdError::ConnectionFailed(format!("{}: {}", socket_path, e))
+    /// Connect to greetd at `target`: a socket path, `@name` for a Linux
+    /// abstract-namespace socket, or `tcp:host:port` (needs the `tcp` feature)
+    pub async fn connect_to(target: &str) -> GreetdResult<Self> {
+        let connected: io::Result<Box<dyn IpcStream>> = if let Some(name) = target.strip_prefix('@') {
+            connect_abstract(name).map(|s| Box::new(s) as Box<dyn IpcStream>)
+        } else if let Some(addr) = target.strip_prefix("tcp:") {
+            connect_tcp(addr).await
+        } else {
+            UnixStream::connect(target).await.map(|s| Box::new(s) as Box<dyn IpcStream>)
+        };
+
+        let stream = connected.map_err(|e| {
+            GreetdError::ConnectionFailed(format!("{}: {}", target, e))
         })?;
-        Ok(Self { stream })
+        Ok(Self { stream, cancelled_stale: false })
     }
 
     /// Authenticate a user with password
@@ -51,25 +125,15 @@ impl GreetdClient {
         username: &str,
         password: &str,
     ) -> GreetdResult<()> {
-        let request = Request::CreateSession {
-            username: username.to_string(),
-        };
-        self.send_request(request).await?;
-
-        match self.read_response().await? {
+        match self.create_session(username).await? {
             Response::AuthMessage { auth_message_type, .. } => {
                 match auth_message_type {
-                    AuthMessageType::Secret { .. } => {
+                    AuthMessageType::Secret => {
                         self.send_password(password).await?;
                     }
                     _ => return Err(GreetdError::Protocol("Unexpected auth message type".into())),
                 }
             }
-            Response::Error { error_type, description } => {
-                return Err(GreetdError::AuthFailed(
-                    format!("{:?}: {}", error_type, description),
-                ));
-            }
             _ => return Err(GreetdError::Protocol("Unexpected response during auth".into())),
         }
 
@@ -78,37 +142,66 @@ impl GreetdClient {
 
     /// Send password response
     async fn send_password(&mut self, password: &str) -> GreetdResult<()> {
-        let request = Request::PostAuthMessageResponse {
-            response: Some(password.to_string()),
-        };
-        self.send_request(request).await?;
+        match self.post_response(Some(password.to_string())).await? {
+            Response::Success => Ok(()),
+            _ => Err(GreetdError::Protocol("Unexpected response after password".into())),
+        }
+    }
+
+    /// Begin a login conversation for `username`. Returns greetd's first
+    /// reply (`Success` or an `AuthMessage`); an error reply becomes `Err`.
+    ///
+    /// A conversation left open by an earlier greeter (one that crashed or
+    /// was killed before cancelling) makes greetd refuse a new one; that is
+    /// cancelled and the request retried once; see
+    /// [`take_cancelled_stale`](Self::take_cancelled_stale).
+    pub async fn create_session(&mut self, username: &str) -> GreetdResult<Response> {
+        match self.request_session(username).await {
+            Err(e) if e.is_session_in_progress() => {
+                self.cancelled_stale = true;
+                self.cancel_session().await?;
+                self.request_session(username).await
+            }
+            result => result,
+        }
+    }
+
+    /// Whether a stale conversation was cancelled since the last call
+    pub fn take_cancelled_stale(&mut self) -> bool {
+        std::mem::take(&mut self.cancelled_stale)
+    }
+
+    async fn request_session(&mut self, username: &str) -> GreetdResult<Response> {
+        self.send_request(Request::CreateSession {
+            username: username.to_string(),
+        }).await?;
+        self.read_reply().await
+    }
+
+    /// Answer the last auth message (`None` acknowledges Info/Error messages).
+    /// Returns greetd's next reply; an error reply becomes `Err`.
+    pub async fn post_response(&mut self, response: Option<String>) -> GreetdResult<Response> {
+        self.send_request(Request::PostAuthMessageResponse { response }).await?;
+        self.read_reply().await
+    }
 
+    /// Read a response, turning greetd's `Error` reply into an error
+    async fn read_reply(&mut self) -> GreetdResult<Response> {
         match self.read_response().await? {
-            Response::Success => Ok(()),
             Response::Error { error_type, description } => {
-                Err(GreetdError::AuthFailed(
-                    format!("{:?}: {}", error_type, description),
-                ))
+                Err(GreetdError::from_reply(error_type, description))
             }
-            _ => Err(GreetdError::Protocol("Unexpected response after password".into())),
+            response => Ok(response),
         }
     }
 
-    /// Start a session with the specified command
-    pub async fn start_session(&mut self, command: &str) -> GreetdResult<()> {
-        let cmd_parts: Vec<String> = command
-            .split_whitespace()
-            .map(|s| s.to_string())
-            .collect();
-
-        if cmd_parts.is_empty() {
+    /// Start a session with the specified argv and extra `KEY=VALUE` environment
+    pub async fn start_session(&mut self, cmd: Vec<String>, env: Vec<String>) -> GreetdResult<()> {
+        if cmd.is_empty() {
             return Err(GreetdError::SessionFailed("Empty session command".into()));
         }
 
-        let request = Request::StartSession {
-            cmd: cmd_parts,
-            env: vec![],
-        };
+        let request = Request::StartSession { cmd, env };
 
         self.send_request(request).await?;
 
@@ -169,10 +262,36 @@ impl GreetdClient {
     }
 }
 
+#[cfg(target_os = "linux")]
+fn connect_abstract(name: &str) -> io::Result<UnixStream> {
+    use std::os::linux::net::SocketAddrExt;
+    let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+    let stream = std::os::unix::net::UnixStream::connect_addr(&addr)?;
+    stream.set_nonblocking(true)?;
+    UnixStream::from_std(stream)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn connect_abstract(_name: &str) -> io::Result<UnixStream> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "abstract sockets are Linux-only"))
+}
+
+#[cfg(feature = "tcp")]
+async fn connect_tcp(addr: &str) -> io::Result<Box<dyn IpcStream>> {
+    let stream = tokio::net::TcpStream::connect(addr).await?;
+    stream.set_nodelay(true)?;
+    Ok(Box::new(stream))
+}
+
+#[cfg(not(feature = "tcp"))]
+async fn connect_tcp(_addr: &str) -> io::Result<Box<dyn IpcStream>> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "built without the tcp feature"))
+}
+
 /// Convenience function for full authentication flow
-pub async fn login(username: &str, password: &str, session: &str) -> GreetdResult<()> {
+pub async fn login(username: &str, password: &str, session: Vec<String>) -> GreetdResult<()> {
     let mut client = GreetdClient::connect().await?;
     client.authenticate(username, password).await?;
-    client.start_session(session).await?;
+    client.start_session(session, Vec::new()).await?;
     Ok(())
 }
@@ -0,0 +1,96 @@
+use crate::session::split_words;
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How long a feedback sound command may run before it is killed
+const SOUND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long `dbus-send` may take when no bus answers
+const DBUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Object path, interface and member of the `hooks.dbus_notify` signal
+const DBUS_PATH: &str = "/org/hyprgreeter/Greeter";
+const DBUS_SIGNAL: &str = "org.hyprgreeter.Greeter.LoggedIn";
+
+/// Run a hook command without waiting for it. `env` is added to the
+/// greeter's environment; the child is reaped on a background thread.
+pub fn spawn(command: &str, env: &[(&str, String)]) -> Result<(), String> {
+    let mut child = start(&split_words(command)?, env)?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Like [`spawn`], but the child is killed if it is still running after `timeout`
+pub fn spawn_with_timeout(command: &str, timeout: Duration) -> Result<(), String> {
+    reap_with_timeout(start(&split_words(command)?, &[])?, timeout);
+    Ok(())
+}
+
+/// Wait for `child` on a background thread, killing it after `timeout`
+fn reap_with_timeout(mut child: Child, timeout: Duration) {
+    std::thread::spawn(move || {
+        let deadline = Instant::now() + timeout;
+        while let Ok(None) = child.try_wait() {
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    });
+}
+
+/// Audible feedback for `ui.bell_on_*` / `ui.*_sound_cmd`
+pub fn feedback(bell: bool, command: Option<&str>) -> Result<(), String> {
+    if bell {
+        let mut stdout = std::io::stdout();
+        let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+    }
+    match command.filter(|c| !c.trim().is_empty()) {
+        Some(command) => spawn_with_timeout(command, SOUND_TIMEOUT),
+        None => Ok(()),
+    }
+}
+
+/// `hooks.dbus_notify`: broadcast a `LoggedIn(user, session)` signal on the
+/// system bus through `dbus-send`, without waiting. Only a missing
+/// `dbus-send` is an error; a missing bus only makes the detached child fail.
+pub fn dbus_login_signal(username: &str, session: &str) -> Result<(), String> {
+    let argv = [
+        "dbus-send".to_string(),
+        "--system".to_string(),
+        "--type=signal".to_string(),
+        DBUS_PATH.to_string(),
+        DBUS_SIGNAL.to_string(),
+        format!("string:{}", username),
+        format!("string:{}", session),
+    ];
+    reap_with_timeout(start(&argv, &[])?, DBUS_TIMEOUT);
+    Ok(())
+}
+
+fn start(argv: &[String], env: &[(&str, String)]) -> Result<Child, String> {
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| "Empty hook command".to_string())?;
+
+    Command::new(program)
+        .args(args)
+        .envs(env.iter().map(|(k, v)| (*k, v.as_str())))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run hook {}: {}", program, e))
+}
+
+/// Run `hooks.on_failure` for a failed login, exposing the username as
+/// `HYPR_GREETER_USER` and the failure count as `HYPR_GREETER_ATTEMPTS`
+pub fn on_failure(command: &str, username: &str, attempts: u32) -> Result<(), String> {
+    spawn(command, &[
+        ("HYPR_GREETER_USER", username.to_string()),
+        ("HYPR_GREETER_ATTEMPTS", attempts.to_string()),
+    ])
+}
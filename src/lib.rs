@@ -0,0 +1,686 @@
+//! hypr-greeter: a TUI greeter for greetd.
+//!
+//! The binary is a thin wrapper around [`run_greeter`]; the modules are public
+//! so the UI state, renderer and greetd client can be reused on their own.
+
+pub mod auth;
+pub mod backlight;
+pub mod banner;
+pub mod bootstrap;
+pub mod config;
+pub mod doctor;
+pub mod greetd_client;
+pub mod hooks;
+pub mod power;
+pub mod session;
+pub mod sysinfo;
+pub mod ui;
+pub mod users;
+pub mod widgets;
+
+use config::Config;
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use auth::{Authenticator, GreetdAuthenticator};
+use greetd_client::{GreetdError, GreetdResult};
+use greetd_ipc::{AuthMessageType, Response};
+use power::PowerAction;
+use ratatui::{backend::Backend, layout::Rect, Terminal};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use ui::{App, Focus, MessageLevel};
+
+/// How long to wait for input before checking the shutdown flag again
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Tick length while an animation is running
+const ANIMATION_TICK: Duration = Duration::from_millis(40);
+/// Consecutive failed frames tolerated before the loop gives up
+const MAX_DRAW_FAILURES: u32 = 5;
+/// How often the screen is redrawn while waiting on greetd
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(150);
+/// Percent of the backlight range one Ctrl+Up/Down changes
+const BRIGHTNESS_STEP: i32 = 10;
+
+/// How the greeter loop ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// Authentication succeeded and the session was handed to greetd
+    LoggedIn { username: String },
+    /// The user left the greeter without logging in (Ctrl+Esc or Ctrl+C)
+    Quit,
+    /// The shutdown flag was raised (e.g. SIGTERM) before anyone logged in
+    Terminated,
+    /// The user quit after the last login attempt could not reach greetd
+    GreetdUnreachable,
+    /// With `--once`, the single login attempt failed
+    LoginFailed,
+}
+
+/// Process exit codes of the greeter binary, for wrapper scripts and
+/// service managers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// A session was handed to greetd
+    SessionStarted = 0,
+    /// An unexpected error (terminal, runtime)
+    Error = 1,
+    /// The user quit or the greeter was stopped without a login
+    Cancelled = 2,
+    /// The config could not be loaded (bootstrap), or the greeter ran on the
+    /// built-in defaults because of it and was left without a login
+    ConfigError = 3,
+    /// greetd could not be reached
+    GreetdUnreachable = 4,
+    /// The only login attempt (`--once`) failed
+    LoginFailed = 5,
+}
+
+impl ExitCode {
+    /// Exit code for how the greeter loop ended; `config_error` tells whether
+    /// it ran on the fallback config
+    pub fn from_outcome(outcome: &Outcome, config_error: bool) -> Self {
+        match outcome {
+            Outcome::LoggedIn { .. } => ExitCode::SessionStarted,
+            Outcome::GreetdUnreachable => ExitCode::GreetdUnreachable,
+            Outcome::LoginFailed => ExitCode::LoginFailed,
+            Outcome::Quit | Outcome::Terminated if config_error => ExitCode::ConfigError,
+            Outcome::Quit | Outcome::Terminated => ExitCode::Cancelled,
+        }
+    }
+
+    /// The numeric process exit status
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+}
+
+/// Version and build information printed by `--version`
+pub fn version_info() -> String {
+    format!(
+        "hypr-greeter {} (git {})\ngreetd-ipc protocol {}",
+        env!("CARGO_PKG_VERSION"),
+        env!("HYPR_GREETER_GIT_HASH"),
+        greetd_client::GREETD_IPC_VERSION,
+    )
+}
+
+/// Run the greeter event loop on an already set-up terminal until the user
+/// logs in or quits. Terminal setup and cleanup are left to the caller.
+pub async fn run_greeter<B: Backend>(
+    config: Config,
+    terminal: &mut Terminal<B>,
+) -> Result<Outcome, Box<dyn Error>> {
+    run_greeter_with_shutdown(config, terminal, Arc::new(AtomicBool::new(false))).await
+}
+
+/// Like [`run_greeter`], but also returns [`Outcome::Terminated`] once
+/// `shutdown` is set, after cancelling any open greetd session.
+pub async fn run_greeter_with_shutdown<B: Backend>(
+    config: Config,
+    terminal: &mut Terminal<B>,
+    shutdown: Arc<AtomicBool>,
+) -> Result<Outcome, Box<dyn Error>> {
+    let auth = GreetdAuthenticator::from_config(config.greetd_socket.as_deref());
+    run_greeter_with_auth(config, terminal, shutdown, Box::new(auth)).await
+}
+
+/// Like [`run_greeter_with_shutdown`], but authenticating through `auth`
+/// instead of greetd.
+pub async fn run_greeter_with_auth<B: Backend>(
+    config: Config,
+    terminal: &mut Terminal<B>,
+    shutdown: Arc<AtomicBool>,
+    mut auth: Box<dyn Authenticator>,
+) -> Result<Outcome, Box<dyn Error>> {
+    let mut app = App::new(config);
+    if let Err(e) = ui::check_timezone(&app.config.ui) {
+        app.set_error(e);
+    }
+    run_app(terminal, &mut app, auth.as_mut(), &shutdown).await
+}
+
+async fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    auth: &mut dyn Authenticator,
+    shutdown: &AtomicBool,
+) -> Result<Outcome, Box<dyn Error>> {
+    let result = event_loop(terminal, app, auth, shutdown).await;
+    // Whatever ended the loop, don't leave greetd holding a half-created session
+    cancel_auth(app, auth).await;
+    result
+}
+
+/// Where a greetd conversation stands after the latest exchange
+enum AuthStep {
+    /// Credentials accepted and the session handed to greetd
+    Started,
+    /// greetd asked another question; the user answers in the password field
+    NeedsInput,
+}
+
+/// Drive the login conversation through `auth`, which keeps it open so it
+/// can be cancelled if anything goes wrong. A fresh attempt answers the first
+/// prompt with the typed password; while a conversation is open, the password
+/// field holds the answer to the latest prompt instead.
+async fn attempt_login(
+    app: &mut App,
+    auth: &mut dyn Authenticator,
+    session_argv: Vec<String>,
+    redraw: &mut dyn FnMut(&App),
+) -> GreetdResult<AuthStep> {
+    let result = login_steps(app, auth, session_argv, redraw).await;
+    app.auth_started = None;
+    result
+}
+
+async fn login_steps(
+    app: &mut App,
+    auth: &mut dyn Authenticator,
+    session_argv: Vec<String>,
+    redraw: &mut dyn FnMut(&App),
+) -> GreetdResult<AuthStep> {
+    let answer = app.password.clone();
+    let (first, pending) = if app.auth_open {
+        (wait_for(app, redraw, auth.post_response(Some(answer))).await?, VecDeque::new())
+    } else {
+        app.auth_open = true;
+        app.clear_pam_messages();
+        // `security.prompt_form`: answers for the prompts after the password
+        let mut pending = VecDeque::from([answer]);
+        pending.extend(app.prompt_answers.iter_mut().map(std::mem::take));
+        let username = app.login_name().to_string();
+        let first = wait_for(app, redraw, auth.create_session(&username)).await;
+        if let Some(notice) = auth.take_notice() {
+            app.push_pam_message(MessageLevel::Info, notice);
+        }
+        (first?, pending)
+    };
+
+    match converse(app, auth, first, pending, redraw).await? {
+        AuthStep::Started => {}
+        AuthStep::NeedsInput => return Ok(AuthStep::NeedsInput),
+    }
+    let home = users::home_dir(app.login_name());
+    let mut env = session::build_env(&app.config, app.current_session(), home.as_deref());
+    if app.config.ui.extra_secret.is_some() && !app.extra_secret.is_empty() {
+        env.push(format!("{}={}", app.config.ui.extra_secret_env, app.extra_secret));
+    }
+    ui::wipe(&mut app.extra_secret);
+    wait_for(app, redraw, auth.start_session(session_argv, env)).await?;
+    app.auth_open = false;
+    Ok(AuthStep::Started)
+}
+
+/// Await one greetd request. While it is outstanding the screen is redrawn
+/// every [`PROGRESS_INTERVAL`] with the progress line (`App::auth_started`),
+/// so slow PAM modules show a spinner rather than a frozen screen.
+async fn wait_for<T>(
+    app: &mut App,
+    redraw: &mut dyn FnMut(&App),
+    request: impl std::future::Future<Output = T>,
+) -> T {
+    tokio::pin!(request);
+    loop {
+        tokio::select! {
+            result = &mut request => return result,
+            _ = tokio::time::sleep(PROGRESS_INTERVAL) => {
+                app.auth_started.get_or_insert_with(Instant::now);
+                redraw(app);
+            }
+        }
+    }
+}
+
+/// Process greetd replies until it either accepts the login or asks a
+/// question we have no answer for. `answers` go to the prompts in order;
+/// once they run out the next prompt is asked in the password field. Prompt
+/// texts are kept in `app` so the password field can show what PAM asked.
+async fn converse(
+    app: &mut App,
+    auth: &mut dyn Authenticator,
+    mut reply: Response,
+    mut answers: VecDeque<String>,
+    redraw: &mut dyn FnMut(&App),
+) -> GreetdResult<AuthStep> {
+    loop {
+        reply = match reply {
+            Response::Success => return Ok(AuthStep::Started),
+            Response::AuthMessage { auth_message_type, auth_message } => match auth_message_type {
+                // Some PAM stacks ask for the login name again; we already know it
+                AuthMessageType::Visible if auth::is_username_prompt(&auth_message) => {
+                    let name = app.login_name().to_string();
+                    wait_for(app, redraw, auth.post_response(Some(name))).await?
+                }
+                AuthMessageType::Secret | AuthMessageType::Visible => {
+                    app.set_auth_prompt(auth_message, matches!(auth_message_type, AuthMessageType::Visible));
+                    match answers.pop_front() {
+                        Some(answer) => wait_for(app, redraw, auth.post_response(Some(answer))).await?,
+                        None => return Ok(AuthStep::NeedsInput),
+                    }
+                }
+                AuthMessageType::Info => {
+                    app.push_pam_message(MessageLevel::Info, auth_message);
+                    wait_for(app, redraw, auth.post_response(None)).await?
+                }
+                AuthMessageType::Error => {
+                    app.push_pam_message(MessageLevel::Error, auth_message);
+                    wait_for(app, redraw, auth.post_response(None)).await?
+                }
+            },
+            Response::Error { error_type, description } => {
+                return Err(GreetdError::from_reply(error_type, description));
+            }
+        };
+    }
+}
+
+/// Cancel the open auth session, if any, and drop the connection
+async fn cancel_auth(app: &mut App, auth: &mut dyn Authenticator) {
+    if app.auth_open {
+        let _ = auth.cancel_session().await;
+        app.auth_open = false;
+    }
+    auth.close();
+}
+
+/// Show "Welcome, <user>" for `ui.success_delay_ms` before the greeter
+/// exits and greetd starts the session; any key press cuts it short.
+fn show_welcome<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<(), Box<dyn Error>> {
+    let delay = Duration::from_millis(app.config.ui.success_delay_ms);
+    if delay.is_zero() {
+        return Ok(());
+    }
+    app.success_message = Some(format!("Welcome, {}", app.login_name()));
+    terminal.draw(|f| ui::draw(f, app))?;
+
+    let deadline = Instant::now() + delay;
+    while let Some(left) = deadline.checked_duration_since(Instant::now()).filter(|d| !d.is_zero()) {
+        if event::poll(left)? && matches!(event::read()?, Event::Key(_)) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Draw one frame, returning whether it reached the terminal. A failed write
+/// (e.g. a flaky TTY) resets the terminal so the next attempt repaints from
+/// scratch; only `MAX_DRAW_FAILURES` failures in a row end the greeter.
+/// Nothing is printed here, as stderr is the terminal being drawn on; the
+/// final error is reported by the caller once the terminal is restored.
+fn draw_frame<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &App,
+    failures: &mut u32,
+) -> std::io::Result<bool> {
+    match terminal.draw(|f| ui::draw(f, app)) {
+        Ok(_) => {
+            *failures = 0;
+            Ok(true)
+        }
+        Err(e) => {
+            *failures += 1;
+            if *failures >= MAX_DRAW_FAILURES {
+                let message = format!("draw failed {} times in a row: {}", failures, e);
+                return Err(std::io::Error::new(e.kind(), message));
+            }
+            let _ = terminal.clear();
+            Ok(false)
+        }
+    }
+}
+
+async fn event_loop<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    auth: &mut dyn Authenticator,
+    shutdown: &AtomicBool,
+) -> Result<Outcome, Box<dyn Error>> {
+    // Frames are only drawn when input or a tick changed the state, or the
+    // clock text moved on; an idle greeter redraws once per clock change
+    // instead of on every poll timeout.
+    let mut drawn_clock = String::new();
+    let mut draw_failures = 0;
+    // Ticks follow their own deadline, so typing does not hold them back
+    let mut last_tick = Instant::now();
+    loop {
+        if shutdown.load(Ordering::SeqCst) {
+            return Ok(Outcome::Terminated);
+        }
+
+        let clock = ui::clock_text(&app.config);
+        if (app.dirty || clock != drawn_clock) && draw_frame(terminal, app, &mut draw_failures)? {
+            app.dirty = false;
+            drawn_clock = clock;
+        }
+
+        let tick_rate = if app.is_animating() { ANIMATION_TICK } else { POLL_INTERVAL };
+        let has_event = event::poll(tick_rate.saturating_sub(last_tick.elapsed()))?;
+        if last_tick.elapsed() >= tick_rate {
+            last_tick = Instant::now();
+            app.on_tick();
+            if let Some(action) = app.idle_action_due() {
+                match action.as_str() {
+                    "exit" => return Ok(app.quit_outcome()),
+                    "suspend" => {
+                        if let Err(e) = power::execute(PowerAction::Suspend, &app.config.power) {
+                            app.set_error(e);
+                        }
+                    }
+                    "screensaver" => app.screensaver = true,
+                    _ => {
+                        cancel_auth(app, auth).await;
+                        app.clear_input();
+                    }
+                }
+                app.dirty = true;
+            }
+        }
+        if !has_event {
+            continue;
+        }
+
+        let event = event::read()?;
+        // Resize the backend to the reported size (clearing stale cells) so
+        // the next draw lays everything out for the new dimensions
+        if let Event::Resize(width, height) = event {
+            terminal.resize(Rect::new(0, 0, width, height))?;
+            app.dirty = true;
+            continue;
+        }
+
+        if let Event::Key(key) = event {
+            app.dirty = true;
+            if !matches!(key.code, KeyCode::F(1) | KeyCode::F(2)) {
+                app.cancel_power();
+            }
+            if key.code != KeyCode::Enter {
+                app.cancel_session_confirm();
+            }
+
+            app.last_input = Instant::now();
+            // The blank screen ignores everything but the unlock sequence
+            if app.blank_locked {
+                if let KeyCode::Char(c) = key.code {
+                    app.feed_unlock(c);
+                }
+                continue;
+            }
+
+            // A key only wakes the screensaver, it is not typed anywhere
+            if app.screensaver {
+                app.screensaver = false;
+                continue;
+            }
+
+            // The system info panel is modal too, and read-only
+            if app.system_info.is_some() {
+                let esc = key.code == KeyCode::Esc && app.config.ui.esc_closes_overlay();
+                if key.code == KeyCode::F(9) || esc {
+                    app.system_info = None;
+                }
+                continue;
+            }
+
+            // The launch preview is modal: it only listens for its close keys
+            if app.show_debug {
+                let esc = key.code == KeyCode::Esc && app.config.ui.esc_closes_overlay();
+                if key.code == KeyCode::F(12) || esc {
+                    app.show_debug = false;
+                }
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Tab => {
+                    app.clear_error();
+                    app.next_focus();
+                }
+                KeyCode::BackTab => {
+                    app.clear_error();
+                    app.prev_focus();
+                }
+
+                // Like a web form: Enter on a filled username moves on to the password
+                KeyCode::Enter
+                    if app.focus == Focus::Username
+                        && !app.username.is_empty()
+                        && app.password.is_empty() =>
+                {
+                    app.clear_error();
+                    app.set_focus(Focus::Password);
+                }
+
+                KeyCode::Enter => {
+                    let password_ok = !app.password.is_empty() || app.config.security.allow_empty_password;
+                    if !app.login_name().is_empty() && password_ok {
+                        let session_argv = match app.current_session() {
+                            Some(s) => session::build_argv(s, &app.config.session),
+                            None => {
+                                app.set_error(ui::NO_SESSIONS.to_string());
+                                continue;
+                            }
+                        };
+                        let session_argv = match session_argv {
+                            Ok(argv) => argv,
+                            Err(e) => {
+                                app.set_error(e);
+                                continue;
+                            }
+                        };
+
+                        if !app.auth_open && !app.confirm_session() {
+                            continue;
+                        }
+
+                        let mut redraw = |app: &App| {
+                            let _ = terminal.draw(|f| ui::draw(f, app));
+                        };
+                        let login_result = attempt_login(app, auth, session_argv, &mut redraw).await;
+                        match login_result {
+                            Ok(AuthStep::NeedsInput) => {
+                                app.password.clear();
+                                app.set_focus(Focus::Password);
+                            }
+                            Ok(AuthStep::Started) => {
+                                app.reset_attempts();
+                                let ui = &app.config.ui;
+                                if let Err(e) = hooks::feedback(ui.bell_on_success, ui.success_sound_cmd.as_deref()) {
+                                    app.push_pam_message(MessageLevel::Error, e);
+                                }
+                                let session = app.current_session().map(|s| s.name.clone());
+                                // Best effort: nobody is left to read an error once the session starts
+                                if app.config.hooks.dbus_notify {
+                                    let name = session.as_deref().unwrap_or_default();
+                                    let _ = hooks::dbus_login_signal(app.login_name(), name);
+                                }
+                                if let Err(e) = config::save_last_user(app.login_name(), session.as_deref()) {
+                                    app.push_pam_message(MessageLevel::Error, format!("Failed to save last_user: {}", e));
+                                }
+                                show_welcome(terminal, app)?;
+                                return Ok(Outcome::LoggedIn { username: app.login_name().to_string() });
+                            }
+                            Err(e) => {
+                                cancel_auth(app, auth).await;
+                                app.greetd_unreachable = matches!(e, GreetdError::ConnectionFailed(_));
+                                let ui = &app.config.ui;
+                                if let Err(e) = hooks::feedback(ui.bell_on_error, ui.error_sound_cmd.as_deref()) {
+                                    app.push_pam_message(MessageLevel::Error, e);
+                                }
+                                // Only a rejected password is the user's mistake: anything
+                                // else keeps the password and does not count as an attempt
+                                if matches!(e, GreetdError::AuthFailed(_)) {
+                                    app.record_failed_attempt();
+                                    if let Some(command) = app.failure_hook_due() {
+                                        // Shown under the error: stderr is the screen the TUI draws on
+                                        if let Err(e) = hooks::on_failure(&command, app.login_name(), app.failed_attempts) {
+                                            app.push_pam_message(MessageLevel::Error, e);
+                                        }
+                                    }
+                                    app.set_error(format!("Login failed: {}", e));
+                                } else {
+                                    app.show_error(format!("Login failed: {} (press Enter to retry)", e));
+                                }
+                                if app.config.once {
+                                    return Ok(if app.greetd_unreachable {
+                                        Outcome::GreetdUnreachable
+                                    } else {
+                                        Outcome::LoginFailed
+                                    });
+                                }
+                            }
+                        }
+                    } else {
+                        app.set_error("Please enter username and password".to_string());
+                    }
+                }
+
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(app.quit_outcome());
+                }
+
+                // Ctrl+L: start over after fumbling the input
+                KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    cancel_auth(app, auth).await;
+                    app.start_over();
+                }
+
+                // Readline-style editing: Ctrl+U clears the field, Ctrl+W drops a word
+                KeyCode::Char(c @ ('u' | 'w')) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    app.clear_error();
+                    if app.focus == Focus::Username {
+                        cancel_auth(app, auth).await;
+                        app.autofilled = false;
+                        app.reset_attempts();
+                    }
+                    if c == 'u' {
+                        app.kill_to_start();
+                    } else {
+                        app.kill_word();
+                    }
+                }
+
+                // Alt+digit jumps straight to a session without touching the fields
+                KeyCode::Char(c @ '1'..='9') if key.modifiers.contains(KeyModifiers::ALT) => {
+                    app.clear_error();
+                    app.select_session(c as usize - '1' as usize);
+                }
+
+                KeyCode::Char(c) => {
+                    app.clear_error();
+                    match app.focus {
+                        Focus::Username => {
+                            if app.push_username_char(c) {
+                                cancel_auth(app, auth).await;
+                                app.reset_attempts();
+                            }
+                        }
+                        Focus::Password => app.push_password_char(c),
+                        Focus::ExtraSecret => app.extra_secret.push(c),
+                        Focus::Prompt(i) => app.prompt_answers[i].push(c),
+                        Focus::Session => {}
+                    }
+                }
+
+                KeyCode::Backspace => {
+                    app.clear_error();
+                    match app.focus {
+                        Focus::Username => {
+                            cancel_auth(app, auth).await;
+                            app.username.pop();
+                            app.autofilled = false;
+                            app.reset_attempts();
+                        }
+                        Focus::Password => app.pop_password_char(),
+                        Focus::ExtraSecret => { app.extra_secret.pop(); }
+                        Focus::Prompt(i) => { app.prompt_answers[i].pop(); }
+                        Focus::Session => {}
+                    }
+                }
+
+                // Ctrl+Up/Down: backlight brightness, before the plain arrow keys
+                KeyCode::Up | KeyCode::Down
+                    if app.config.ui.allow_brightness && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    let step = if key.code == KeyCode::Up { BRIGHTNESS_STEP } else { -BRIGHTNESS_STEP };
+                    match backlight::adjust(step) {
+                        Ok(level) => app.show_brightness(level),
+                        Err(e) => app.show_error(format!("Brightness: {}", e)),
+                    }
+                }
+
+                KeyCode::Up if app.focus == Focus::Username => {
+                    app.clear_error();
+                    cancel_auth(app, auth).await;
+                    app.prev_user();
+                }
+                KeyCode::Down if app.focus == Focus::Username => {
+                    app.clear_error();
+                    cancel_auth(app, auth).await;
+                    app.next_user();
+                }
+
+                KeyCode::Left if app.focus == Focus::Session => {
+                    app.clear_error();
+                    app.prev_session();
+                }
+                KeyCode::Right if app.focus == Focus::Session => {
+                    app.clear_error();
+                    app.next_session();
+                }
+
+                KeyCode::F(n @ (1 | 2)) => {
+                    app.clear_error();
+                    let action = if n == 1 { PowerAction::Poweroff } else { PowerAction::Reboot };
+                    if let Some(action) = app.request_power(action) {
+                        if let Err(e) = power::execute(action, &app.config.power) {
+                            app.set_error(e);
+                        }
+                    }
+                }
+
+                KeyCode::PageUp => app.scroll_pam_messages(true),
+                KeyCode::PageDown => app.scroll_pam_messages(false),
+
+                KeyCode::F(5) => {
+                    app.clear_error();
+                    app.refresh_sessions();
+                }
+
+                KeyCode::F(9) if app.config.ui.system_info => {
+                    app.system_info = Some(sysinfo::gather());
+                }
+
+                KeyCode::F(12) if app.config.ui.debug_overlay => {
+                    app.show_debug = true;
+                }
+
+                KeyCode::Esc if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(app.quit_outcome());
+                }
+
+                // Plain Esc: `ui.esc_action` (panels were handled above)
+                KeyCode::Esc => match app.config.ui.esc_action.as_str() {
+                    "clear_field" => {
+                        app.clear_error();
+                        if app.focus == Focus::Username {
+                            cancel_auth(app, auth).await;
+                            app.autofilled = false;
+                            app.reset_attempts();
+                        }
+                        app.kill_to_start();
+                    }
+                    "clear_all" => {
+                        cancel_auth(app, auth).await;
+                        app.start_over();
+                    }
+                    _ => {}
+                },
+
+                _ => {}
+            }
+        }
+    }
+}
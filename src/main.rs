@@ -1,13 +1,13 @@
-mod bootstrap;
-mod config;
-mod greetd_client;
-mod ui;
-
-use config::load_config;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyModifiers},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-    ExecutableCommand,
+    cursor::{Hide, MoveTo, SetCursorStyle, Show},
+    style::Print,
+    terminal::{self, disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand, QueueableCommand,
+};
+use std::io::Write;
+use hypr_greeter::{
+    bootstrap, config::{load_config_or_default, UiConfig}, greetd_client, run_greeter_with_shutdown, ui,
+    ExitCode,
 };
 use ratatui::{
     backend::CrosstermBackend,
@@ -15,9 +15,70 @@ use ratatui::{
 };
 use std::error::Error;
 use std::io;
-use ui::{App, Focus};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::signal::unix::{signal, SignalKind};
+
+/// How long the event loop gets to cancel greetd and unwind after a signal
+/// before the terminal is restored and the process exits regardless
+const SIGNAL_GRACE: Duration = Duration::from_secs(2);
+
+/// Setting this (to anything but "0") has the same effect as `--no-altscreen`
+const NO_ALTSCREEN_ENV: &str = "HYPR_GREETER_NO_ALTSCREEN";
+
+/// Whether the terminal setup entered the alternate screen, so every cleanup
+/// path (normal exit, panic, signal) leaves it only when it was entered
+static ALT_SCREEN: AtomicBool = AtomicBool::new(true);
+
+/// `--no-altscreen` / `$HYPR_GREETER_NO_ALTSCREEN`: draw on the main screen
+/// so the output stays in the scrollback for debugging
+fn no_altscreen_requested() -> bool {
+    std::env::args().skip(1).any(|a| a == "--no-altscreen")
+        || std::env::var(NO_ALTSCREEN_ENV).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// `--once` ends the greeter after the first login attempt; `--persist` (the
+/// default) keeps prompting. The last of the two given wins.
+fn once_requested() -> bool {
+    std::env::args()
+        .skip(1)
+        .rfind(|a| a == "--once" || a == "--persist")
+        .is_some_and(|a| a == "--once")
+}
+
+/// `--verbose`: log how the config was resolved and which greetd socket is
+/// used at startup
+fn verbose_requested() -> bool {
+    std::env::args().skip(1).any(|a| a == "--verbose")
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
+    if matches!(std::env::args().nth(1).as_deref(), Some("--version" | "-V")) {
+        println!("{}", hypr_greeter::version_info());
+        return Ok(());
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("--doctor") {
+        let healthy = hypr_greeter::doctor::run();
+        std::process::exit(if healthy { 0 } else { 1 });
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("--session-list") {
+        let config = load_config_or_default();
+        print!("{}", hypr_greeter::session::session_list(&config));
+        if let Some(e) = config.load_error {
+            eprintln!("hypr-greeter: using default config: {}", e);
+            std::process::exit(ExitCode::ConfigError.code());
+        }
+        return Ok(());
+    }
+
+    if std::env::args().nth(1).as_deref() == Some("--print-schema") {
+        print!("{}", hypr_greeter::config::CONFIG_SCHEMA);
+        return Ok(());
+    }
+
     if std::env::args().nth(1).as_deref() == Some("--bootstrap") {
         let rest: Vec<String> = std::env::args().skip(2).collect();
         let parsed = match bootstrap::parse_args(&rest) {
@@ -31,11 +92,16 @@ fn main() -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
 
+    ALT_SCREEN.store(!no_altscreen_requested(), Ordering::SeqCst);
+
     // Install panic hook to restore terminal on panic
     let default_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |info| {
         let _ = disable_raw_mode();
-        let _ = io::stdout().execute(LeaveAlternateScreen);
+        if ALT_SCREEN.load(Ordering::SeqCst) {
+            let _ = io::stdout().execute(LeaveAlternateScreen);
+        }
+        let _ = io::stdout().execute(Show);
         default_hook(info);
     }));
 
@@ -44,132 +110,102 @@ fn main() -> Result<(), Box<dyn Error>> {
 }
 
 async fn async_main() -> Result<(), Box<dyn Error>> {
-    let config = load_config()?;
+    let mut config = load_config_or_default();
+    if let Some(ref e) = config.load_error {
+        eprintln!("hypr-greeter: using default config: {}", e);
+    }
+    config.once = once_requested();
+    if verbose_requested() {
+        for line in hypr_greeter::config::config_report(&config).lines() {
+            eprintln!("hypr-greeter: {}", line);
+        }
+        eprintln!(
+            "hypr-greeter: {}",
+            greetd_client::protocol_summary(&greetd_client::socket_target(
+                config.greetd_socket.as_deref()
+            ))
+        );
+    }
 
-    setup_terminal()?;
+    let shutdown = Arc::new(AtomicBool::new(false));
+    spawn_signal_handler(shutdown.clone())?;
+
+    setup_terminal(&config.ui)?;
 
     let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new(config.clone());
-
-    let result = run_app(&mut terminal, &mut app).await;
+    let config_error = config.load_error.is_some();
+    let result = run_greeter_with_shutdown(config, &mut terminal, shutdown).await;
 
     cleanup_terminal()?;
 
-    if let Err(e) = result {
-        eprintln!("Error: {}", e);
-        std::process::exit(1);
-    }
+    let code = match result {
+        Ok(outcome) => ExitCode::from_outcome(&outcome, config_error),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::Error
+        }
+    };
+    std::process::exit(code.code());
+}
 
+/// Raise `shutdown` on SIGTERM/SIGINT so the greeter loop can cancel greetd
+/// and restore the terminal. If the loop is stuck (e.g. waiting on PAM),
+/// restore the terminal from here and exit after a grace period.
+fn spawn_signal_handler(shutdown: Arc<AtomicBool>) -> io::Result<()> {
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let mut sigint = signal(SignalKind::interrupt())?;
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = sigterm.recv() => {}
+            _ = sigint.recv() => {}
+        }
+        shutdown.store(true, Ordering::SeqCst);
+        tokio::time::sleep(SIGNAL_GRACE).await;
+        let _ = cleanup_terminal();
+        std::process::exit(ExitCode::Cancelled.code());
+    });
     Ok(())
 }
 
-fn setup_terminal() -> Result<(), Box<dyn Error>> {
+/// Some VTs keep stale glyphs or the old cursor position across the
+/// alternate-screen switch, so both directions also clear and home explicitly.
+fn setup_terminal(ui_config: &UiConfig) -> Result<(), Box<dyn Error>> {
     enable_raw_mode()?;
-    io::stdout().execute(EnterAlternateScreen)?;
+    let mut stdout = io::stdout();
+    if ALT_SCREEN.load(Ordering::SeqCst) {
+        stdout.queue(EnterAlternateScreen)?;
+    }
+    // Hidden until a frame places it on a focused text field
+    stdout
+        .queue(Clear(ClearType::All))?
+        .queue(MoveTo(0, 0))?
+        .queue(Hide)?
+        .queue(ui::cursor_style(ui_config))?;
+    stdout.flush()?;
     Ok(())
 }
 
 fn cleanup_terminal() -> Result<(), Box<dyn Error>> {
-    disable_raw_mode()?;
-    io::stdout().execute(LeaveAlternateScreen)?;
-    Ok(())
-}
-
-async fn run_app<B: ratatui::backend::Backend>(
-    terminal: &mut Terminal<B>,
-    app: &mut App,
-) -> Result<(), Box<dyn Error>> {
-    loop {
-        terminal.draw(|f| ui::draw(f, app))?;
-
-        if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Tab => {
-                    app.clear_error();
-                    app.next_focus();
-                }
-                KeyCode::BackTab => {
-                    app.clear_error();
-                    app.prev_focus();
-                }
-
-                KeyCode::Enter => {
-                    if !app.username.is_empty() && !app.password.is_empty() {
-                        let session_cmd = match app.current_session_command() {
-                            Some(cmd) => cmd.to_string(),
-                            None => {
-                                app.set_error("No sessions configured".to_string());
-                                continue;
-                            }
-                        };
-
-                        let login_result = greetd_client::login(
-                            &app.username,
-                            &app.password,
-                            &session_cmd,
-                        ).await;
-                        match login_result {
-                            Ok(_) => {
-                                if let Err(e) = crate::config::save_last_user(&app.username) {
-                                    eprintln!("Failed to save last_user: {}", e);
-                                }
-                                break;
-                            }
-                            Err(e) => {
-                                if let Ok(mut client) = greetd_client::GreetdClient::connect().await {
-                                    let _ = client.cancel_session().await;
-                                }
-                                app.set_error(format!("Login failed: {}", e));
-                            }
-                        }
-                    } else {
-                        app.set_error("Please enter username and password".to_string());
-                    }
-                }
-
-                KeyCode::Char(c) => {
-                    app.clear_error();
-                    match app.focus {
-                        Focus::Username => app.username.push(c),
-                        Focus::Password => app.password.push(c),
-                        Focus::Session => {}
-                    }
-                }
-
-                KeyCode::Backspace => {
-                    app.clear_error();
-                    match app.focus {
-                        Focus::Username => { app.username.pop(); }
-                        Focus::Password => { app.password.pop(); }
-                        Focus::Session => {}
-                    }
-                }
-
-                KeyCode::Left => {
-                    if app.focus == Focus::Session {
-                        app.clear_error();
-                        app.prev_session();
-                    }
-                }
-                KeyCode::Right => {
-                    if app.focus == Focus::Session {
-                        app.clear_error();
-                        app.next_session();
-                    }
-                }
-
-                KeyCode::Esc => {
-                    if key.modifiers.contains(KeyModifiers::CONTROL) {
-                        break;
-                    }
-                }
-
-                _ => {}
-            }
-        }
+    let mut stdout = io::stdout();
+    stdout.queue(SetCursorStyle::DefaultUserShape)?;
+    if ALT_SCREEN.load(Ordering::SeqCst) {
+        stdout.queue(Clear(ClearType::All))?.queue(MoveTo(0, 0))?;
+        stdout.flush()?;
+        disable_raw_mode()?;
+        stdout.queue(LeaveAlternateScreen)?;
+        // The console underneath is left blank with a visible cursor at the top
+        stdout.queue(Clear(ClearType::All))?.queue(MoveTo(0, 0))?;
+    } else {
+        // Keep the last frame in the scrollback and continue below it
+        let (_, rows) = terminal::size().unwrap_or((0, 1));
+        stdout.queue(MoveTo(0, rows.saturating_sub(1)))?;
+        stdout.flush()?;
+        disable_raw_mode()?;
+        stdout.queue(Print("\n"))?;
     }
-
+    stdout.queue(Show)?;
+    stdout.flush()?;
     Ok(())
 }
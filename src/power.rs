@@ -0,0 +1,62 @@
+use crate::config::PowerConfig;
+use crate::session::split_words;
+use std::process::{Command, Stdio};
+
+/// Power actions available from the login screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerAction {
+    Poweroff,
+    Reboot,
+    /// Only run by the idle timeout, never from a key
+    Suspend,
+}
+
+impl PowerAction {
+    /// Verb used in status messages ("Press again to reboot")
+    pub fn verb(self) -> &'static str {
+        match self {
+            PowerAction::Poweroff => "power off",
+            PowerAction::Reboot => "reboot",
+            PowerAction::Suspend => "suspend",
+        }
+    }
+
+    /// Whether this action needs a second key press
+    pub fn needs_confirm(self, cfg: &PowerConfig) -> bool {
+        match self {
+            PowerAction::Poweroff => cfg.confirm_poweroff,
+            PowerAction::Reboot => cfg.confirm_reboot,
+            PowerAction::Suspend => false,
+        }
+    }
+
+    /// Whether a command is set for this action
+    pub fn is_configured(self, cfg: &PowerConfig) -> bool {
+        !self.command(cfg).trim().is_empty()
+    }
+
+    fn command(self, cfg: &PowerConfig) -> &str {
+        match self {
+            PowerAction::Poweroff => &cfg.poweroff_command,
+            PowerAction::Reboot => &cfg.reboot_command,
+            PowerAction::Suspend => &cfg.suspend_command,
+        }
+    }
+}
+
+/// Spawn the configured command for a power action without waiting for it
+pub fn execute(action: PowerAction, cfg: &PowerConfig) -> Result<(), String> {
+    let argv = split_words(action.command(cfg))?;
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| format!("No command configured to {}", action.verb()))?;
+
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to {}: {}", action.verb(), e))?;
+    Ok(())
+}
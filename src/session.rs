@@ -0,0 +1,521 @@
+use crate::config::{Config, Session, SessionOptions};
+use std::path::{Path, PathBuf};
+
+/// `PATH` for sessions when neither `session.path` nor the greeter's
+/// environment provides one
+pub const DEFAULT_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/bin:/usr/sbin:/bin:/sbin";
+
+/// System-wide environment file read for `session.load_environment_files`
+const SYSTEM_ENV_FILE: &str = "/etc/environment";
+
+/// Directories scanned by `session.autodiscover`
+const WAYLAND_SESSION_DIR: &str = "/usr/share/wayland-sessions";
+const X_SESSION_DIR: &str = "/usr/share/xsessions";
+
+/// Drop-in directory for session definitions installed by packages
+const SYSTEM_DROP_IN_DIR: &str = "/etc/hypr-greeter/sessions.d";
+
+/// greetd cannot start an X server itself, so X sessions run under this
+const X_SESSION_WRAPPER: &str = "startx /usr/bin/env";
+
+/// Command marking the "Default / Last" entry added by
+/// `session.last_session_entry`; it is resolved before login, never run
+pub const LAST_SESSION: &str = "@last";
+
+/// Build the argv sent to greetd for a session.
+///
+/// Shell sessions are handed to `/bin/sh -lc` verbatim so the shell does its
+/// own expansion; everything else is split into words with `$VAR` expanded
+/// as it goes (not inside single quotes or after a backslash), so a value
+/// containing spaces stays a single argument.
+///
+/// greetd's `start_session` request has no working directory, so a `cwd` is
+/// applied with a `cd` first: prepended to the script of a shell session, or
+/// through a small `/bin/sh` wrapper that then execs the original argv.
+pub fn build_argv(session: &Session, opts: &SessionOptions) -> Result<Vec<String>, String> {
+    let cwd = session.cwd.as_deref().map(|dir| expand_env(dir, opts)).transpose()?;
+
+    if session.shell {
+        let script = match &cwd {
+            Some(dir) => format!("cd {} && {}", shell_quote(dir), session.command),
+            None => session.command.clone(),
+        };
+        return Ok(vec!["/bin/sh".to_string(), "-lc".to_string(), script]);
+    }
+
+    let argv = split_and_expand(&session.command, opts)?;
+    if argv.is_empty() {
+        return Err("Empty session command".to_string());
+    }
+    match cwd {
+        Some(dir) => {
+            let mut wrapped = vec![
+                "/bin/sh".to_string(),
+                "-c".to_string(),
+                "cd \"$0\" && exec \"$@\"".to_string(),
+                dir,
+            ];
+            wrapped.extend(argv);
+            Ok(wrapped)
+        }
+        None => Ok(argv),
+    }
+}
+
+/// Quote `s` as a single word for `/bin/sh`
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Split a command into words the way a POSIX shell would, honoring single
+/// quotes, double quotes and backslash escapes. No expansion is performed.
+pub fn split_words(command: &str) -> Result<Vec<String>, String> {
+    split(command, None)
+}
+
+/// [`split_words`], expanding `$VAR` and `${VAR}` like [`expand_env`] where a
+/// shell would: unquoted and inside double quotes, but not inside single
+/// quotes or as a backslash-escaped `\$`
+pub fn split_and_expand(command: &str, opts: &SessionOptions) -> Result<Vec<String>, String> {
+    split(command, Some(opts))
+}
+
+fn split(command: &str, expand: Option<&SessionOptions>) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => word.push(ch),
+                        None => return Err(format!("unterminated single quote in: {}", command)),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        // Inside double quotes a backslash only escapes these
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\' | '$' | '`')) => word.push(ch),
+                            Some(ch) => {
+                                word.push('\\');
+                                word.push(ch);
+                            }
+                            None => return Err(format!("unterminated double quote in: {}", command)),
+                        },
+                        Some('$') if expand.is_some() => {
+                            expand_var(&mut chars, command, expand.unwrap(), &mut word)?;
+                        }
+                        Some(ch) => word.push(ch),
+                        None => return Err(format!("unterminated double quote in: {}", command)),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                match chars.next() {
+                    Some(ch) => word.push(ch),
+                    None => return Err(format!("trailing backslash in: {}", command)),
+                }
+            }
+            '$' if expand.is_some() => {
+                in_word = true;
+                expand_var(&mut chars, command, expand.unwrap(), &mut word)?;
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+
+    Ok(words)
+}
+
+/// Expand `$VAR` and `${VAR}` references in a session command against the
+/// greeter's environment. No shell is involved — this is plain substitution.
+///
+/// Undefined variables expand to an empty string, or produce an error when
+/// `session.strict_env` is set.
+pub fn expand_env(command: &str, opts: &SessionOptions) -> Result<String, String> {
+    let mut out = String::with_capacity(command.len());
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '$' {
+            expand_var(&mut chars, command, opts, &mut out)?;
+        } else {
+            out.push(c);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Expand the variable reference following a `$` already taken from `chars`
+/// into `out`
+fn expand_var(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    command: &str,
+    opts: &SessionOptions,
+    out: &mut String,
+) -> Result<(), String> {
+    let name = match chars.peek() {
+        Some('{') => {
+            chars.next();
+            let mut name = String::new();
+            loop {
+                match chars.next() {
+                    Some('}') => break,
+                    Some(ch) => name.push(ch),
+                    None => return Err(format!("unterminated ${{ in: {}", command)),
+                }
+            }
+            name
+        }
+        Some(ch) if ch.is_ascii_alphabetic() || *ch == '_' => {
+            let mut name = String::new();
+            while let Some(&ch) = chars.peek() {
+                if ch.is_ascii_alphanumeric() || ch == '_' {
+                    name.push(ch);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            name
+        }
+        _ => {
+            // Lone `$` (e.g. trailing or `$1`) is kept literally
+            out.push('$');
+            return Ok(());
+        }
+    };
+
+    match std::env::var(&name) {
+        Ok(value) => out.push_str(&value),
+        Err(_) if opts.strict_env => {
+            return Err(format!("undefined variable ${} in session command", name));
+        }
+        Err(_) => {}
+    }
+    Ok(())
+}
+
+/// Where `program` would be run from: itself if it contains a `/`, else the
+/// first executable match in the directories of `path` (a `PATH` value)
+pub fn find_program(program: &str, path: &str) -> Option<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+    let executable = |p: &Path| p.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0);
+    if program.contains('/') {
+        let p = PathBuf::from(program);
+        return executable(&p).then_some(p);
+    }
+    std::env::split_paths(path)
+        .map(|dir| dir.join(program))
+        .find(|p| executable(p))
+}
+
+/// Environment sent to greetd with `session`, as `KEY=VALUE` strings.
+///
+/// Sources are merged key by key, each overriding the ones before it:
+/// 1. `PATH` from `session.path`, else the greeter's own, else
+///    [`DEFAULT_PATH`], so bare command names resolve even where greetd
+///    sets none
+/// 2. with `session.load_environment_files`, `/etc/environment` followed by
+///    the user's `~/.config/environment.d/*.conf` (in file-name order), the
+///    way a graphical login would provide them
+/// 3. the global `session_env`
+/// 4. the session's own `env`
+pub fn build_env(config: &Config, session: Option<&Session>, home: Option<&Path>) -> Vec<String> {
+    let mut vars = Vec::new();
+    let path = match &config.session.path {
+        Some(path) => path.clone(),
+        None => std::env::var("PATH").ok().filter(|p| !p.is_empty()).unwrap_or_else(|| DEFAULT_PATH.to_string()),
+    };
+    if !path.is_empty() {
+        set_var(&mut vars, "PATH", &path);
+    }
+    if config.session.load_environment_files {
+        for (key, value) in read_env_files(&env_files(home)).iter().filter_map(|v| v.split_once('=')) {
+            set_var(&mut vars, key, value);
+        }
+    }
+    let session_env = session.map(|s| s.env.as_slice()).unwrap_or_default();
+    for assignment in config.session_env.iter().chain(session_env) {
+        if let Some((key, value)) = assignment.split_once('=') {
+            set_var(&mut vars, key, value);
+        }
+    }
+    vars.into_iter().map(|(k, v)| format!("{}={}", k, v)).collect()
+}
+
+/// Set `key` in `vars`, replacing an earlier value in place
+fn set_var(vars: &mut Vec<(String, String)>, key: &str, value: &str) {
+    match vars.iter_mut().find(|(k, _)| k == key) {
+        Some(existing) => existing.1 = value.to_string(),
+        None => vars.push((key.to_string(), value.to_string())),
+    }
+}
+
+/// Environment files for a user with home directory `home`, lowest precedence first
+pub fn env_files(home: Option<&Path>) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(SYSTEM_ENV_FILE)];
+    if let Some(home) = home {
+        let dir = home.join(".config").join("environment.d");
+        let mut user_files: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "conf"))
+            .collect();
+        user_files.sort();
+        files.extend(user_files);
+    }
+    files
+}
+
+/// Merge `KEY=VALUE` assignments from `files` (missing or unreadable ones are
+/// skipped); a later assignment of the same key replaces the earlier value.
+pub fn read_env_files(files: &[PathBuf]) -> Vec<String> {
+    let mut vars: Vec<(String, String)> = Vec::new();
+    for file in files {
+        let Ok(content) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        for (key, value) in parse_env_file(&content) {
+            set_var(&mut vars, &key, &value);
+        }
+    }
+    vars.into_iter().map(|(k, v)| format!("{}={}", k, v)).collect()
+}
+
+/// Parse simple `KEY=VALUE` lines, skipping blanks, comments and anything
+/// that is not an assignment. One pair of surrounding quotes is removed.
+pub fn parse_env_file(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return None;
+            }
+            let value = value.trim();
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+                .unwrap_or(value);
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// The session list to offer: the configured sessions, then the drop-in
+/// sessions of `drop_ins` (usually [`drop_in_dirs`]), then (with
+/// `session.autodiscover`) installed sessions, each skipping commands already
+/// listed. With `session.last_session_entry` a "Default / Last" marker entry
+/// goes first. Also returns the drop-in files that were skipped and why.
+pub fn effective_sessions(
+    configured: &[Session],
+    opts: &SessionOptions,
+    drop_ins: &[PathBuf],
+) -> (Vec<Session>, Vec<String>) {
+    let mut sessions = configured.to_vec();
+    let (mut found, errors) = read_drop_in_sessions(drop_ins);
+    if opts.autodiscover {
+        found.extend(discover_sessions());
+    }
+    for session in found {
+        if !sessions.iter().any(|s| s.command == session.command) {
+            sessions.push(session);
+        }
+    }
+    if opts.last_session_entry && !sessions.is_empty() {
+        sessions.insert(0, Session::new("Default / Last", LAST_SESSION));
+    }
+    (sessions, errors)
+}
+
+/// `--session-list`: one tab-separated `name`, `command`, `type` line per
+/// effective session, in selector order. The type is `exec`, `shell`,
+/// `drop-in` (from a sessions.d file), `discovered` (from a desktop entry) or
+/// `last` (the "Default / Last" entry). Skipped drop-in files are reported
+/// on stderr.
+pub fn session_list(config: &Config) -> String {
+    let dirs = drop_in_dirs();
+    let (drop_ins, _) = read_drop_in_sessions(&dirs);
+    let (sessions, errors) = effective_sessions(&config.sessions, &config.session, &dirs);
+    for e in errors {
+        eprintln!("hypr-greeter: {}", e);
+    }
+    sessions
+        .iter()
+        .map(|s| {
+            let listed = |list: &[Session]| list.iter().any(|c| c.command == s.command);
+            let kind = if s.command == LAST_SESSION {
+                "last"
+            } else if s.shell {
+                "shell"
+            } else if listed(&config.sessions) {
+                "exec"
+            } else if listed(&drop_ins) {
+                "drop-in"
+            } else {
+                "discovered"
+            };
+            format!("{}\t{}\t{}\n", s.name, s.command, kind)
+        })
+        .collect()
+}
+
+/// The session offered by `security.allow_fallback_shell` when there is no
+/// other: the user's login shell, as greetd sets `$SHELL`
+pub fn fallback_shell() -> Session {
+    Session { shell: true, ..Session::new("Shell (fallback)", "exec \"${SHELL:-/bin/sh}\" -l") }
+}
+
+/// The session `session` stands for: itself, or for the [`LAST_SESSION`]
+/// marker the last used session (by name) among `sessions`, else the first
+/// real one
+pub fn resolve_session<'a>(
+    session: &'a Session,
+    sessions: &'a [Session],
+    last_session: Option<&str>,
+) -> Option<&'a Session> {
+    if session.command != LAST_SESSION {
+        return Some(session);
+    }
+    let real: Vec<&Session> = sessions.iter().filter(|s| s.command != LAST_SESSION).collect();
+    last_session
+        .and_then(|name| real.iter().find(|s| s.name == name))
+        .or_else(|| real.first())
+        .copied()
+}
+
+/// Directories holding drop-in session files: `/etc/hypr-greeter/sessions.d`,
+/// then `$XDG_CONFIG_HOME/hypr-greeter/sessions.d`
+pub fn drop_in_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from(SYSTEM_DROP_IN_DIR)];
+    if let Some(dir) = dirs::config_dir() {
+        dirs.push(dir.join("hypr-greeter").join("sessions.d"));
+    }
+    dirs
+}
+
+/// Sessions from the `*.toml` and `*.json` files in `dirs`, one session per
+/// file with the same keys as a `[[sessions]]` entry. Each directory is read
+/// in file name order; files that do not parse are skipped, with one
+/// message per file in the second list.
+pub fn read_drop_in_sessions(dirs: &[PathBuf]) -> (Vec<Session>, Vec<String>) {
+    let mut sessions = Vec::new();
+    let mut errors = Vec::new();
+    for dir in dirs {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml" || ext == "json"))
+            .collect();
+        files.sort();
+        for file in files {
+            let Ok(content) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            let parsed = if file.extension().is_some_and(|ext| ext == "json") {
+                serde_json::from_str::<Session>(&content).map_err(|e| e.to_string())
+            } else {
+                toml::from_str::<Session>(&content).map_err(|e| e.to_string())
+            };
+            match parsed {
+                Ok(session) => sessions.push(session),
+                Err(e) => errors.push(format!("Ignoring {}: {}", file.display(), e)),
+            }
+        }
+    }
+    (sessions, errors)
+}
+
+/// Sessions installed as desktop entries in the wayland-sessions and
+/// xsessions directories, each sorted by file name
+pub fn discover_sessions() -> Vec<Session> {
+    let mut sessions = Vec::new();
+    for (dir, wrapper) in [(WAYLAND_SESSION_DIR, None), (X_SESSION_DIR, Some(X_SESSION_WRAPPER))] {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "desktop"))
+            .collect();
+        files.sort();
+        for file in files {
+            let Ok(content) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            if let Some(mut session) = parse_desktop_entry(&content) {
+                if let Some(wrapper) = wrapper {
+                    session.command = format!("{} {}", wrapper, session.command);
+                }
+                sessions.push(session);
+            }
+        }
+    }
+    sessions
+}
+
+/// Read `Name` and `Exec` from a session desktop entry. Entries marked
+/// `Hidden` or `NoDisplay` are skipped, and `%f`-style field codes are
+/// dropped from the command.
+pub fn parse_desktop_entry(content: &str) -> Option<Session> {
+    let mut in_entry = false;
+    let (mut name, mut exec) = (None, None);
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "Name" => name = Some(value.trim().to_string()),
+            "Exec" => exec = Some(value.trim().to_string()),
+            "Hidden" | "NoDisplay" if value.trim() == "true" => return None,
+            _ => {}
+        }
+    }
+
+    let command = exec?
+        .split_whitespace()
+        .filter(|word| !(word.len() == 2 && word.starts_with('%')))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if command.is_empty() {
+        return None;
+    }
+    Some(Session { name: name?, command, ..Default::default() })
+}
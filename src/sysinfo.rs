@@ -0,0 +1,42 @@
+//! `ui.system_info`: a read-only panel describing the machine, for support
+//! staff at the login screen. Everything is read when the panel opens.
+
+use crate::greetd_client;
+
+/// Label/value rows for the panel; unreadable sources show "unknown"
+pub fn gather() -> Vec<(&'static str, String)> {
+    let read = |path: &str| std::fs::read_to_string(path).ok();
+    let or_unknown = |value: Option<String>| value.unwrap_or_else(|| "unknown".to_string());
+    vec![
+        ("Host", or_unknown(crate::ui::read_hostname())),
+        ("Distro", or_unknown(read("/etc/os-release").as_deref().and_then(parse_os_release))),
+        ("Kernel", or_unknown(read("/proc/sys/kernel/osrelease").map(|v| v.trim().to_string()))),
+        ("Uptime", or_unknown(crate::widgets::uptime())),
+        ("Memory", or_unknown(read("/proc/meminfo").as_deref().and_then(parse_meminfo))),
+        ("Greeter", format!("hypr-greeter {}", env!("CARGO_PKG_VERSION"))),
+        ("greetd", format!("greetd-ipc protocol {}", greetd_client::GREETD_IPC_VERSION)),
+    ]
+}
+
+/// The distribution name from os-release: `PRETTY_NAME`, else `NAME`
+pub fn parse_os_release(content: &str) -> Option<String> {
+    let field = |key: &str| {
+        content.lines().find_map(|line| {
+            let value = line.strip_prefix(key)?.strip_prefix('=')?;
+            Some(value.trim().trim_matches('"').to_string())
+        })
+    };
+    field("PRETTY_NAME").or_else(|| field("NAME")).filter(|name| !name.is_empty())
+}
+
+/// Used and total memory from /proc/meminfo, e.g. "3.1 / 15.5 GiB"
+pub fn parse_meminfo(content: &str) -> Option<String> {
+    let kib = |key: &str| {
+        content.lines().find_map(|line| {
+            line.strip_prefix(key)?.strip_prefix(':')?.split_whitespace().next()?.parse::<u64>().ok()
+        })
+    };
+    let (total, available) = (kib("MemTotal")?, kib("MemAvailable")?);
+    let gib = |kib: u64| kib as f64 / (1024.0 * 1024.0);
+    Some(format!("{:.1} / {:.1} GiB", gib(total.saturating_sub(available)), gib(total)))
+}
@@ -1,38 +1,157 @@
-use crate::config::Config;
-use chrono::Local;
+use crate::config::{Config, Session};
+use crate::power::PowerAction;
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
+use crossterm::cursor::SetCursorStyle;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{block::{Position, Title}, Block, BorderType, Borders, Clear, Paragraph, Wrap},
     Frame,
 };
 
+/// Shown when there is no session to log into
+pub const NO_SESSIONS: &str = "No sessions available \u{2014} install a compositor or configure one";
+
+/// Rows the banner may take; a longer file is cut off
+const BANNER_MAX_ROWS: u16 = 10;
+
+/// Ticks a focus border takes to fade in when `ui.animations` is on
+pub const FOCUS_ANIM_TICKS: u8 = 6;
+
 /// Application state for the UI
 pub struct App {
     pub username: String,
     pub password: String,
+    /// Extra secret collected for the session when `ui.extra_secret` is set
+    pub extra_secret: String,
+    /// Answers typed into the `security.prompt_form` fields, in prompt order
+    pub prompt_answers: Vec<String>,
+    /// The kept password is selected: the next keystroke replaces it
+    pub password_selected: bool,
+    /// When the last password character was typed, for `security.reveal_last_char`
+    pub password_typed_at: Option<Instant>,
     pub selected_session: usize,
     pub focus: Focus,
+    /// Focus fade-in progress per field (indexed by `Focus::index`)
+    pub focus_phase: Vec<u8>,
+    /// Users offered on the username field when `ui.user_list` is on
+    pub users: Vec<String>,
+    /// Hostname shown under the title when `ui.show_hostname` is on (read once)
+    pub hostname: Option<String>,
+    /// `[[sessions]]` as configured, before autodiscovered ones are added
+    pub configured_sessions: Vec<Session>,
+    /// Where drop-in sessions are read from (see [`crate::session::drop_in_dirs`])
+    pub drop_in_dirs: Vec<PathBuf>,
+    /// Whether the username was filled in by autofill rather than typed
+    pub autofilled: bool,
     pub error_message: Option<String>,
+    /// When the error message dismisses itself (`ui.error_timeout_secs`)
+    pub error_deadline: Option<Instant>,
+    /// Non-error feedback shown in the status line (e.g. power confirmation)
+    pub status_message: Option<String>,
+    /// PAM Info/Error messages of the current login attempt, oldest first
+    pub pam_messages: Vec<(MessageLevel, String)>,
+    /// How many lines the PAM message area is scrolled back from the newest
+    pub pam_scroll: usize,
+    /// Welcome message shown between a successful login and exit
+    pub success_message: Option<String>,
+    /// Failed login attempts for the current username
+    pub failed_attempts: u32,
+    /// Whether greetd has a created-but-not-started session for us
+    pub auth_open: bool,
+    /// Whether the last login attempt failed to reach greetd
+    pub greetd_unreachable: bool,
+    /// Latest prompt text from greetd/PAM, used as the password field title
+    pub auth_prompt: Option<String>,
+    /// Whether the latest prompt asked for visible (unmasked) input
+    pub auth_echo: bool,
+    /// Whether the launch preview overlay is open
+    pub show_debug: bool,
+    /// Rows of the open system info panel (`ui.system_info`, F9)
+    pub system_info: Option<Vec<(&'static str, String)>>,
+    /// When the greetd request now outstanding started to take noticeably
+    /// long; shows the progress line
+    pub auth_started: Option<Instant>,
+    /// Backlight level in percent and when it was set, while its bar shows
+    pub brightness: Option<(u8, Instant)>,
+    /// Whether state changed since the last frame was drawn
+    pub dirty: bool,
+    /// Power action waiting for its confirming second press
+    pub pending_power: Option<(PowerAction, Instant)>,
+    /// Session (by index) waiting for its confirming second Enter
+    pub pending_session: Option<(usize, Instant)>,
+    /// When `hooks.on_failure` last ran, for rate limiting
+    pub last_failure_hook: Option<Instant>,
+    /// Blanked by `security.blank_after_failures`; only the unlock sequence works
+    pub blank_locked: bool,
+    /// Keys typed on the blank screen, matched against the unlock sequence
+    unlock_input: String,
+    /// Blanked by `security.idle_action = "screensaver"`; any key wakes it
+    pub screensaver: bool,
+    /// Time of the last key press, for `security.input_timeout`
+    pub last_input: Instant,
+    /// Latest output of the `ui.widgets` commands
+    pub widget_outputs: crate::widgets::CommandOutputs,
+    /// `ui.banner_file`, kept current on each tick
+    pub banner: Option<crate::banner::Banner>,
+    /// What the terminal can display, from `ui.color_mode`
+    pub color_depth: ColorDepth,
     pub config: Config,
 }
 
+/// Severity of a PAM message
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageLevel {
+    Info,
+    Error,
+}
+
 /// Which field is currently focused
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Focus {
     Username,
     Password,
+    ExtraSecret,
     Session,
+    /// A `security.prompt_form` field, by position
+    Prompt(usize),
+}
+
+impl Focus {
+    /// Stable index of the field, for per-field state
+    pub fn index(self) -> usize {
+        match self {
+            Focus::Username => 0,
+            Focus::Password => 1,
+            Focus::ExtraSecret => 2,
+            Focus::Session => 3,
+            Focus::Prompt(i) => 4 + i,
+        }
+    }
+
+    /// The field a name from [`crate::config::FIELDS`] stands for
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "username" => Focus::Username,
+            "password" => Focus::Password,
+            "extra_secret" => Focus::ExtraSecret,
+            _ => Focus::Session,
+        }
+    }
 }
 
 impl App {
     /// Create new app state
-    pub fn new(config: Config) -> Self {
+    pub fn new(mut config: Config) -> Self {
         let autofill = !config.disable_autofill.unwrap_or(false);
         let (username, focus) = if autofill {
-            let user = config.default_user.as_ref().map(|u| u.as_str()).unwrap_or("");
+            let user = config.default_user.as_deref().unwrap_or("");
             let username = if !user.is_empty() {
                 user.to_string()
             } else if let Some(last) = config.last_user.as_ref() {
@@ -40,37 +159,229 @@ impl App {
             } else {
                 String::new()
             };
-            let focus = if username.is_empty() { Focus::Username } else { Focus::Password };
+            let focus = match config.ui.autofill_focus.as_str() {
+                "username" => Focus::Username,
+                "password" => Focus::Password,
+                // "smart": skip to the password when a username was filled in
+                _ if username.is_empty() => Focus::Username,
+                _ => Focus::Password,
+            };
             (username, focus)
         } else {
             (String::new(), Focus::Username)
         };
-        Self {
+        // A locked kiosk account is always the username, and never focused
+        let (username, focus) = match config.locked_user() {
+            Some(user) => (user.to_string(), Focus::Password),
+            None => (username, focus),
+        };
+        let users = if config.ui.user_list {
+            crate::users::load_login_users(&config.ui)
+        } else {
+            Vec::new()
+        };
+        let hostname = if config.ui.show_hostname { read_hostname() } else { None };
+        let configured_sessions = config.sessions.clone();
+        let drop_in_dirs = crate::session::drop_in_dirs();
+        let (sessions, drop_in_errors) = offered_sessions(&configured_sessions, &config, &drop_in_dirs);
+        config.sessions = sessions;
+        // Looked up in the offered list, which may start with "Default / Last"
+        let selected_session = std::env::var(crate::config::SESSION_ENV)
+            .ok()
+            .and_then(|wanted| find_session(&config.sessions, &wanted))
+            .unwrap_or(0);
+        // Nothing, or only the fallback shell, to log into
+        let fallback = crate::session::fallback_shell();
+        let status_message = config.sessions.iter().all(|s| s.command == fallback.command)
+            .then(|| NO_SESSIONS.to_string());
+        let prompts = config.security.prompt_form.len();
+        let mut app = Self {
+            autofilled: !username.is_empty(),
             username,
             password: String::new(),
-            selected_session: 0,
+            extra_secret: String::new(),
+            prompt_answers: vec![String::new(); prompts],
+            password_selected: false,
+            password_typed_at: None,
+            users,
+            hostname,
+            configured_sessions,
+            drop_in_dirs,
+            selected_session,
             focus,
+            focus_phase: vec![FOCUS_ANIM_TICKS; 4 + prompts],
             error_message: None,
+            error_deadline: None,
+            status_message,
+            // Shown in the message log: stderr is the screen being drawn on
+            pam_messages: drop_in_errors.into_iter().map(|e| (MessageLevel::Error, e)).collect(),
+            pam_scroll: 0,
+            success_message: None,
+            failed_attempts: 0,
+            auth_open: false,
+            greetd_unreachable: false,
+            auth_prompt: None,
+            auth_echo: false,
+            show_debug: false,
+            system_info: None,
+            auth_started: None,
+            brightness: None,
+            dirty: true,
+            pending_power: None,
+            pending_session: None,
+            last_failure_hook: None,
+            blank_locked: false,
+            unlock_input: String::new(),
+            screensaver: false,
+            last_input: Instant::now(),
+            widget_outputs: Default::default(),
+            banner: config.ui.banner_file.as_deref().map(crate::banner::Banner::new),
+            color_depth: ColorDepth::from_mode(&config.ui.color_mode),
             config,
+        };
+        // `ui.initial_focus` beats the autofill heuristic, but never lands
+        // on a field that cannot take focus (a locked username)
+        let initial = app.config.ui.initial_focus.as_deref().map(Focus::from_name);
+        if let Some(field) = initial.filter(|f| app.focus_order().contains(f)) {
+            app.focus = field;
         }
+        app
+    }
+
+    /// The shown fields top to bottom: `ui.field_order`, with the
+    /// `security.prompt_form` fields right below the password
+    pub fn form_fields(&self) -> Vec<Focus> {
+        let mut fields = Vec::new();
+        for name in self.config.ui.visible_fields() {
+            let field = Focus::from_name(name);
+            fields.push(field);
+            if field == Focus::Password {
+                fields.extend((0..self.prompt_answers.len()).map(Focus::Prompt));
+            }
+        }
+        fields
+    }
+
+    /// Fields that can take focus, in Tab order
+    pub fn focus_order(&self) -> Vec<Focus> {
+        let locked = self.config.locked_user().is_some();
+        self.form_fields()
+            .into_iter()
+            .filter(|&field| !(locked && field == Focus::Username))
+            .collect()
     }
 
     /// Move focus to next field
     pub fn next_focus(&mut self) {
-        self.focus = match self.focus {
-            Focus::Username => Focus::Password,
-            Focus::Password => Focus::Session,
-            Focus::Session => Focus::Username,
-        };
+        self.step_focus(1);
     }
 
     /// Move focus to previous field
     pub fn prev_focus(&mut self) {
-        self.focus = match self.focus {
-            Focus::Username => Focus::Session,
-            Focus::Password => Focus::Username,
-            Focus::Session => Focus::Password,
-        };
+        self.step_focus(-1);
+    }
+
+    /// Move `step` places through the Tab order, passing over the session
+    /// selector with `ui.tab_skips_session`. Without `ui.focus_wrap` focus
+    /// stays put at either end.
+    fn step_focus(&mut self, step: isize) {
+        let order = self.focus_order();
+        let len = order.len() as isize;
+        let mut pos = order.iter().position(|&f| f == self.focus).unwrap_or(0) as isize;
+        for _ in 0..len {
+            let next = pos + step;
+            if !self.config.ui.focus_wrap && !(0..len).contains(&next) {
+                return;
+            }
+            pos = next.rem_euclid(len);
+            if !(self.config.ui.tab_skips_session && order[pos as usize] == Focus::Session) {
+                self.set_focus(order[pos as usize]);
+                return;
+            }
+        }
+    }
+
+    /// Focus a field, restarting its fade-in animation
+    pub fn set_focus(&mut self, focus: Focus) {
+        if focus != self.focus {
+            self.focus = focus;
+            self.focus_phase[focus.index()] = 0;
+        }
+    }
+
+    /// Whether a focus animation is still running
+    pub fn is_animating(&self) -> bool {
+        self.config.ui.animations && self.focus_phase.iter().any(|&p| p < FOCUS_ANIM_TICKS)
+    }
+
+    /// Advance time-driven state by one tick
+    pub fn on_tick(&mut self) {
+        if self.is_animating() {
+            for phase in &mut self.focus_phase {
+                *phase = (*phase + 1).min(FOCUS_ANIM_TICKS);
+            }
+            self.dirty = true;
+        }
+        if self.password_typed_at.is_some_and(|at| at.elapsed() >= REVEAL_DURATION) {
+            self.password_typed_at = None;
+            self.dirty = true;
+        }
+        if self.widget_outputs.poll(&self.config.ui.widgets) {
+            self.dirty = true;
+        }
+        if self.error_deadline.is_some_and(|at| Instant::now() >= at) {
+            self.clear_error();
+            self.dirty = true;
+        }
+        if self.brightness.is_some_and(|(_, at)| at.elapsed() >= BRIGHTNESS_BAR_DURATION) {
+            self.brightness = None;
+            self.dirty = true;
+        }
+        if self.banner.as_mut().is_some_and(|b| b.refresh()) {
+            self.dirty = true;
+        }
+    }
+
+    /// Show the brightness bar at `level` percent for a moment
+    pub fn show_brightness(&mut self, level: u8) {
+        self.brightness = Some((level, Instant::now()));
+        self.dirty = true;
+    }
+
+    /// The `security.idle_action` to run, once `security.input_timeout`
+    /// seconds passed without input; the idle timer then starts over
+    pub fn idle_action_due(&mut self) -> Option<String> {
+        let timeout = self.config.security.input_timeout;
+        if timeout == 0 || self.last_input.elapsed() < Duration::from_secs(timeout) {
+            return None;
+        }
+        self.last_input = Instant::now();
+        Some(self.config.security.idle_action.clone())
+    }
+
+    /// Wipe the secrets and any messages, keeping the username and session
+    pub fn clear_input(&mut self) {
+        wipe(&mut self.password);
+        wipe(&mut self.extra_secret);
+        self.prompt_answers.iter_mut().for_each(wipe);
+        self.password_selected = false;
+        self.clear_error();
+        self.clear_pam_messages();
+        self.status_message = None;
+        self.dirty = true;
+    }
+
+    /// Ctrl+L: start over with empty fields (a locked username stays),
+    /// focus on the first field and no messages
+    pub fn start_over(&mut self) {
+        self.clear_input();
+        if self.config.locked_user().is_none() {
+            wipe(&mut self.username);
+            self.autofilled = false;
+        }
+        if let Some(&first) = self.focus_order().first() {
+            self.set_focus(first);
+        }
     }
 
     /// Select next session
@@ -89,37 +400,527 @@ impl App {
         }
     }
 
-    /// Get current session command, if any sessions are configured
-    pub fn current_session_command(&self) -> Option<&str> {
-        self.config
-            .sessions
-            .get(self.selected_session)
-            .map(|s| s.command.as_str())
+    /// Fill the username with the next user from the user list
+    pub fn next_user(&mut self) {
+        let next = match self.users.iter().position(|u| *u == self.username) {
+            Some(i) => (i + 1) % self.users.len(),
+            None => 0,
+        };
+        self.pick_user(next);
+    }
+
+    /// Fill the username with the previous user from the user list
+    pub fn prev_user(&mut self) {
+        let prev = match self.users.iter().position(|u| *u == self.username) {
+            Some(0) | None => self.users.len().saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        self.pick_user(prev);
+    }
+
+    fn pick_user(&mut self, index: usize) {
+        if let Some(user) = self.users.get(index) {
+            if *user != self.username {
+                self.username = user.clone();
+                self.autofilled = false;
+                self.reset_attempts();
+            }
+        }
+    }
+
+    /// Select a session by index, ignoring out-of-range indices.
+    /// Returns whether the selection changed.
+    pub fn select_session(&mut self, index: usize) -> bool {
+        if self.config.ui.show_session
+            && index < self.config.sessions.len()
+            && index != self.selected_session
+        {
+            self.selected_session = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// F5: rescan installed sessions, keeping the selected one (matched by
+    /// command) selected when it still exists
+    pub fn refresh_sessions(&mut self) {
+        if !self.config.session.autodiscover {
+            self.status_message = Some("Session autodiscovery is off".to_string());
+            return;
+        }
+        let selected = self.current_session().map(|s| s.command.clone());
+        let (sessions, errors) = offered_sessions(&self.configured_sessions, &self.config, &self.drop_in_dirs);
+        self.config.sessions = sessions;
+        // Pressing F5 again does not repeat a file's message
+        self.pam_messages.retain(|(_, message)| !errors.contains(message));
+        for e in errors {
+            self.push_pam_message(MessageLevel::Error, e);
+        }
+        self.selected_session = selected
+            .and_then(|cmd| self.config.sessions.iter().position(|s| s.command == cmd))
+            .unwrap_or(0);
+        self.status_message = Some(format!("Sessions refreshed ({} available)", self.config.sessions.len()));
+    }
+
+    /// Get current session, if any sessions are configured
+    pub fn current_session(&self) -> Option<&Session> {
+        let session = self.config.sessions.get(self.selected_session)?;
+        crate::session::resolve_session(session, &self.config.sessions, self.config.last_session.as_deref())
+    }
+
+    /// Add a PAM message, scrolling back to the newest
+    pub fn push_pam_message(&mut self, level: MessageLevel, message: String) {
+        self.pam_messages.push((level, message));
+        self.pam_scroll = 0;
+    }
+
+    /// Forget the PAM messages when a new login attempt begins
+    pub fn clear_pam_messages(&mut self) {
+        self.pam_messages.clear();
+        self.pam_scroll = 0;
+    }
+
+    /// PageUp/PageDown: scroll the PAM messages back (`up`) or forward
+    pub fn scroll_pam_messages(&mut self, up: bool) {
+        self.pam_scroll = if up {
+            (self.pam_scroll + 1).min(self.pam_messages.len().saturating_sub(1))
+        } else {
+            self.pam_scroll.saturating_sub(1)
+        };
     }
 
     /// Clear error message
     pub fn clear_error(&mut self) {
         self.error_message = None;
+        self.error_deadline = None;
+    }
+
+    /// Show an error, dismissing it after `ui.error_timeout_secs` if set
+    pub fn show_error(&mut self, message: String) {
+        let timeout = self.config.ui.error_timeout_secs;
+        self.error_message = Some(message);
+        self.error_deadline = (timeout > 0).then(|| Instant::now() + Duration::from_secs(timeout));
+    }
+
+    /// Handle a power key press. Returns the action once it should run:
+    /// immediately when no confirmation is configured, otherwise on a second
+    /// press of the same key within the confirmation timeout.
+    pub fn request_power(&mut self, action: PowerAction) -> Option<PowerAction> {
+        let timeout = Duration::from_secs(self.config.power.confirm_timeout_secs);
+        let confirmed = matches!(
+            self.pending_power,
+            Some((pending, at)) if pending == action && at.elapsed() <= timeout
+        );
+
+        if confirmed || !action.needs_confirm(&self.config.power) {
+            self.cancel_power();
+            return Some(action);
+        }
+
+        self.pending_power = Some((action, Instant::now()));
+        self.status_message = Some(format!("Press again to {}", action.verb()));
+        None
+    }
+
+    /// Drop any pending power confirmation
+    pub fn cancel_power(&mut self) {
+        if self.pending_power.take().is_some() {
+            self.status_message = None;
+        }
+    }
+
+    /// Gate logins into sessions marked `confirm`: the first Enter asks, a
+    /// second one within `power.confirm_timeout_secs` goes ahead. Returns
+    /// whether the login should proceed.
+    pub fn confirm_session(&mut self) -> bool {
+        let index = self.selected_session;
+        let name = match self.current_session() {
+            Some(session) if session.confirm => session.name.clone(),
+            _ => return true,
+        };
+        let timeout = Duration::from_secs(self.config.power.confirm_timeout_secs);
+        let confirmed = matches!(
+            self.pending_session,
+            Some((pending, at)) if pending == index && at.elapsed() <= timeout
+        );
+
+        if confirmed {
+            self.cancel_session_confirm();
+            return true;
+        }
+
+        self.pending_session = Some((index, Instant::now()));
+        self.status_message = Some(format!("Press Enter again to log in to {}", name));
+        false
+    }
+
+    /// Drop any pending session confirmation
+    pub fn cancel_session_confirm(&mut self) {
+        if self.pending_session.take().is_some() {
+            self.status_message = None;
+        }
+    }
+
+    /// Remember the prompt greetd asked with, for the password field title
+    pub fn set_auth_prompt(&mut self, prompt: String, echo: bool) {
+        let prompt = prompt.trim().trim_end_matches(':').trim_end().to_string();
+        self.auth_prompt = if prompt.is_empty() { None } else { Some(prompt) };
+        self.auth_echo = echo;
+    }
+
+    /// Title for the password field: greetd's prompt, or "Password"
+    pub fn password_title(&self) -> &str {
+        self.auth_prompt.as_deref().unwrap_or("Password")
+    }
+
+    /// Count a failed login attempt
+    pub fn record_failed_attempt(&mut self) {
+        self.failed_attempts += 1;
+        let security = &self.config.security;
+        let has_sequence = security.blank_unlock_sequence.as_deref().is_some_and(|seq| !seq.is_empty());
+        if has_sequence
+            && security.blank_after_failures > 0
+            && self.failed_attempts >= security.blank_after_failures
+        {
+            self.blank_locked = true;
+            wipe(&mut self.password);
+            wipe(&mut self.unlock_input);
+        }
+    }
+
+    /// A key typed on the blank screen; restores the form (with a fresh
+    /// attempt count) once the input ends with the unlock sequence
+    pub fn feed_unlock(&mut self, c: char) {
+        let Some(sequence) = self.config.security.blank_unlock_sequence.as_deref() else {
+            return;
+        };
+        self.unlock_input.push(c);
+        if self.unlock_input.ends_with(sequence) {
+            self.blank_locked = false;
+            self.reset_attempts();
+            self.clear_error();
+            wipe(&mut self.unlock_input);
+        } else if self.unlock_input.len() > sequence.len() * 4 {
+            // Only the tail can still match; keep the buffer bounded
+            let keep = self.unlock_input.char_indices().rev().nth(sequence.chars().count()).map_or(0, |(i, _)| i);
+            self.unlock_input.drain(..keep);
+        }
+    }
+
+    /// The `hooks.on_failure` command, if one is configured and it has not
+    /// run within `hooks.on_failure_interval_secs`; marks it as run.
+    pub fn failure_hook_due(&mut self) -> Option<String> {
+        let command = self.config.hooks.on_failure.clone().filter(|c| !c.trim().is_empty())?;
+        let interval = Duration::from_secs(self.config.hooks.on_failure_interval_secs);
+        if self.last_failure_hook.is_some_and(|at| at.elapsed() < interval) {
+            return None;
+        }
+        self.last_failure_hook = Some(Instant::now());
+        Some(command)
+    }
+
+    /// Forget failed attempts (the username changed or login succeeded)
+    pub fn reset_attempts(&mut self) {
+        self.failed_attempts = 0;
+    }
+
+    /// Append a typed character to the username, enforcing
+    /// `security.max_username_len` and `security.username_charset`.
+    /// Rejected characters flash an error instead.
+    pub fn push_username_char(&mut self, c: char) -> bool {
+        if self.config.locked_user().is_some() {
+            return false;
+        }
+        let security = &self.config.security;
+        if !security.username_char_allowed(c) {
+            self.show_error(format!("'{}' is not allowed in usernames", c));
+            return false;
+        }
+        if security.max_username_len > 0 && self.username.chars().count() >= security.max_username_len {
+            let message = format!("Usernames are at most {} characters", security.max_username_len);
+            self.show_error(message);
+            return false;
+        }
+        self.username.push(c);
+        self.autofilled = false;
+        true
+    }
+
+    /// Username sent to greetd: the typed one, with surrounding whitespace
+    /// removed when `security.trim_username` is on. The password is never trimmed.
+    pub fn login_name(&self) -> &str {
+        if self.config.security.trim_username {
+            self.username.trim()
+        } else {
+            &self.username
+        }
+    }
+
+    /// How quitting ends the greeter: plain `Quit`, or `GreetdUnreachable`
+    /// when the last attempt could not reach greetd
+    pub fn quit_outcome(&self) -> crate::Outcome {
+        if self.greetd_unreachable {
+            crate::Outcome::GreetdUnreachable
+        } else {
+            crate::Outcome::Quit
+        }
+    }
+
+    /// Text of the focused input field, if it is one
+    fn focused_text(&mut self) -> Option<&mut String> {
+        match self.focus {
+            Focus::Username => Some(&mut self.username),
+            Focus::Password => {
+                if std::mem::take(&mut self.password_selected) {
+                    wipe(&mut self.password);
+                }
+                Some(&mut self.password)
+            }
+            Focus::ExtraSecret => Some(&mut self.extra_secret),
+            Focus::Prompt(i) => self.prompt_answers.get_mut(i),
+            Focus::Session => None,
+        }
+    }
+
+    /// Ctrl+U: delete everything before the cursor in the focused field
+    pub fn kill_to_start(&mut self) {
+        if let Some(text) = self.focused_text() {
+            wipe(text);
+        }
+    }
+
+    /// Ctrl+W: delete the word before the cursor (and the spaces after it)
+    pub fn kill_word(&mut self) {
+        if let Some(text) = self.focused_text() {
+            let end = text.trim_end().len();
+            let start = text[..end]
+                .char_indices()
+                .rev()
+                .find(|(_, c)| c.is_whitespace())
+                .map_or(0, |(i, c)| i + c.len_utf8());
+            text.truncate(start);
+        }
     }
 
     /// Set error message and optionally clear password
     pub fn set_error(&mut self, message: String) {
-        self.error_message = Some(message);
-        if self.config.security.clear_password_on_error {
-            self.password.clear();
+        self.show_error(message);
+        match self.config.security.password_on_error() {
+            "keep" => {}
+            "select" => self.password_selected = !self.password.is_empty(),
+            _ => wipe(&mut self.password),
+        }
+    }
+
+    /// Type into the password, replacing it if it is selected
+    pub fn push_password_char(&mut self, c: char) {
+        if std::mem::take(&mut self.password_selected) {
+            wipe(&mut self.password);
+        }
+        self.password.push(c);
+        self.password_typed_at = Some(Instant::now());
+    }
+
+    /// Backspace in the password; a selected password is removed whole
+    pub fn pop_password_char(&mut self) {
+        self.password_typed_at = None;
+        if std::mem::take(&mut self.password_selected) {
+            wipe(&mut self.password);
+        } else {
+            self.password.pop();
         }
     }
 }
 
+/// Index of the session whose name, or else command, is `wanted`
+pub fn find_session(sessions: &[Session], wanted: &str) -> Option<usize> {
+    sessions
+        .iter()
+        .position(|s| s.name == wanted)
+        .or_else(|| sessions.iter().position(|s| s.command == wanted))
+}
+
+/// The system hostname, from the kernel or else /etc/hostname
+pub(crate) fn read_hostname() -> Option<String> {
+    ["/proc/sys/kernel/hostname", "/etc/hostname"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .map(|name| name.trim().to_string())
+        .find(|name| !name.is_empty())
+}
+
+/// Empty a secret, overwriting its bytes first so the value does not linger
+/// in freed memory
+pub fn wipe(secret: &mut String) {
+    let mut bytes = std::mem::take(secret).into_bytes();
+    bytes.iter_mut().for_each(|b| *b = 0);
+    std::hint::black_box(&bytes);
+}
+
+/// The `ui.timezone` name, if set
+fn timezone_name(ui: &crate::config::UiConfig) -> Option<&str> {
+    ui.timezone.as_deref().map(str::trim).filter(|tz| !tz.is_empty())
+}
+
+/// Check that `ui.timezone`, when set, is a known IANA zone
+pub fn check_timezone(ui: &crate::config::UiConfig) -> Result<(), String> {
+    match timezone_name(ui) {
+        Some(name) if name.parse::<Tz>().is_err() => Err(format!("Unknown timezone: {}", name)),
+        _ => Ok(()),
+    }
+}
+
+/// Format `now` for the clock and date in `ui.timezone`, or in local time
+/// when it is unset or unknown
+pub fn format_time(now: DateTime<Utc>, ui: &crate::config::UiConfig, fmt: &str) -> String {
+    match timezone_name(ui).and_then(|name| name.parse::<Tz>().ok()) {
+        Some(tz) => now.with_timezone(&tz).format(fmt).to_string(),
+        None => now.with_timezone(&Local).format(fmt).to_string(),
+    }
+}
+
 /// Helper to parse hex colors from config
 fn parse_hex_color(hex: &str) -> Color {
     Color::from_str(hex).unwrap_or(Color::White)
 }
 
+/// Palette a terminal can display
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// The depth for `ui.color_mode`; "auto" trusts `$COLORTERM`, then
+    /// recognises the Linux console and `*-256color` terminals by `$TERM`
+    pub fn from_mode(mode: &str) -> Self {
+        match mode {
+            "truecolor" => ColorDepth::TrueColor,
+            "256" => ColorDepth::Ansi256,
+            "16" => ColorDepth::Ansi16,
+            _ => {
+                let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+                let term = std::env::var("TERM").unwrap_or_default();
+                if colorterm == "truecolor" || colorterm == "24bit" {
+                    ColorDepth::TrueColor
+                } else if term == "linux" || term == "vt100" || term == "ansi" {
+                    ColorDepth::Ansi16
+                } else if term.ends_with("256color") {
+                    ColorDepth::Ansi256
+                } else {
+                    ColorDepth::TrueColor
+                }
+            }
+        }
+    }
+}
+
+/// The standard xterm values of the 16 ANSI colors, in index order
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// The nearest color `depth` can show; only RGB colors change
+pub fn quantize(color: Color, depth: ColorDepth) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    match depth {
+        ColorDepth::TrueColor => color,
+        ColorDepth::Ansi16 => ANSI16
+            .iter()
+            .min_by_key(|(_, rgb)| distance(*rgb, (r, g, b)))
+            .map_or(color, |(named, _)| *named),
+        ColorDepth::Ansi256 => {
+            // Nearest of the 6x6x6 cube (16..=231) and the gray ramp (232..=255)
+            const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+            let level = |v: u8| {
+                (0..6)
+                    .min_by_key(|&i| (LEVELS[i] as i32 - v as i32).abs())
+                    .unwrap_or(0)
+            };
+            let (ri, gi, bi) = (level(r), level(g), level(b));
+            let cube = (LEVELS[ri], LEVELS[gi], LEVELS[bi]);
+            let gray_step = ((r as u32 + g as u32 + b as u32) / 3).saturating_sub(8).min(230) / 10;
+            let gray = 8 + gray_step as u8 * 10;
+            if distance((gray, gray, gray), (r, g, b)) < distance(cube, (r, g, b)) {
+                Color::Indexed(232 + gray_step as u8)
+            } else {
+                Color::Indexed(16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8)
+            }
+        }
+    }
+}
+
+/// The sessions to offer: [`crate::session::effective_sessions`], or the
+/// fallback shell when that is empty and `security.allow_fallback_shell` is
+/// on. Skipped drop-in files come back as messages for the message log.
+fn offered_sessions(configured: &[Session], config: &Config, drop_ins: &[PathBuf]) -> (Vec<Session>, Vec<String>) {
+    let (mut sessions, errors) = crate::session::effective_sessions(configured, &config.session, drop_ins);
+    if sessions.is_empty() && config.security.allow_fallback_shell {
+        sessions.push(crate::session::fallback_shell());
+    }
+    (sessions, errors)
+}
+
 /// Main UI drawing function
 pub fn draw(f: &mut Frame<'_>, app: &App) {
+    draw_screen(f, app);
+    if app.color_depth != ColorDepth::TrueColor {
+        for cell in &mut f.buffer_mut().content {
+            cell.fg = quantize(cell.fg, app.color_depth);
+            cell.bg = quantize(cell.bg, app.color_depth);
+        }
+    }
+}
+
+fn draw_screen(f: &mut Frame<'_>, app: &App) {
     let size = f.size();
 
+    // The deterrent blank screen and the screensaver show nothing at all
+    if app.blank_locked || app.screensaver {
+        f.render_widget(Block::default().style(Style::default().bg(Color::Black)), size);
+        return;
+    }
+
+    let (min_cols, min_rows) = (app.config.ui.min_cols, app.config.ui.min_rows);
+    if size.width < min_cols || size.height < min_rows {
+        draw_too_small(f, size, min_cols, min_rows, &app.config);
+        return;
+    }
+
+    // The help bar takes the top or bottom row; everything else lays out in `body`
+    let help_row = app.config.ui.show_help && size.height > 0;
+    let help_on_top = app.config.ui.help_position == "top";
+    let body = Rect {
+        y: size.y + (help_row && help_on_top) as u16,
+        height: size.height - help_row as u16,
+        ..size
+    };
+
     // Set background color from config
     let bg = parse_hex_color(&app.config.ui.colors.background);
     f.render_widget(
@@ -127,104 +928,248 @@ pub fn draw(f: &mut Frame<'_>, app: &App) {
         size,
     );
 
-    let height = 3u16; // fixed field height (border + content + border)
-    let spacing = app.config.ui.field_spacing as u16;
-    let top_spacing = app.config.ui.top_spacing as u16;
-    let clock_spacing = app.config.ui.clock_spacing as u16;
-    let clock_date_height = if app.config.ui.show_clock && app.config.ui.show_date { 4 } else { 3 };
-
-    // Field width as percentage of terminal width
-    let width = ((size.width as u32 * app.config.ui.field_width / 100) as u16).clamp(20, size.width);
-
-    // Create main layout
-    let chunks = if app.config.ui.show_clock || app.config.ui.show_date {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .margin(2)
-            .constraints([
-                Constraint::Length(3),                  // Title
-                Constraint::Length(top_spacing),        // Top to clock
-                Constraint::Length(clock_date_height),  // Clock/Date
-                Constraint::Length(clock_spacing),      // Clock to fields
-                Constraint::Length(height),             // Username
-                Constraint::Length(spacing),            // Spacing
-                Constraint::Length(height),             // Password
-                Constraint::Length(spacing),            // Spacing
-                Constraint::Length(height),             // Session
-                Constraint::Min(0),                    // Error/Space
-            ])
-            .split(size)
+    // Field height in rows; 3 is the minimum (border + one text line + border).
+    // The compact layout puts each field on a single `Label: input` row.
+    let compact = app.config.ui.layout_style == "compact";
+    let (height, spacing) = if compact {
+        (1, 0)
     } else {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .margin(2)
-            .constraints([
-                Constraint::Length(3),                  // Title
-                Constraint::Length(top_spacing),        // Top to fields
-                Constraint::Length(height),             // Username
-                Constraint::Length(spacing),            // Spacing
-                Constraint::Length(height),             // Password
-                Constraint::Length(spacing),            // Spacing
-                Constraint::Length(height),             // Session
-                Constraint::Min(0),                    // Error/Space
-            ])
-            .split(size)
+        (
+            app.config.ui.field_height.clamp(3, u16::MAX as u32) as u16,
+            app.config.ui.field_spacing as u16,
+        )
+    };
+    let clock_spacing = app.config.ui.clock_spacing as u16;
+    let last_login = last_login_line(&app.config);
+    let show_clock_block = app.config.ui.show_clock || app.config.ui.show_date || last_login.is_some();
+    let clock_date_height = if app.config.ui.show_clock && app.config.ui.show_date { 4 } else { 3 }
+        + last_login.is_some() as u16;
+    let width = field_width(size.width, &app.config.ui);
+    // The banner plus a blank row separating it from the fields
+    let banner = app.banner.as_ref().map(|b| b.text()).filter(|text| !text.is_empty());
+    let banner_rows = banner.map_or(0, |text| wrapped_rows(text, width).min(BANNER_MAX_ROWS) + 1);
+    let banner_below = app.config.ui.banner_position == "below";
+
+    // `ui.top_offset_pct` pins the first field to a fraction of the screen
+    // height; whatever the margin, title and clock don't use becomes spacing
+    let top_spacing = match app.config.ui.top_offset_pct {
+        Some(pct) => {
+            let target = size.y as u32 + size.height as u32 * pct.min(100) / 100;
+            let above = body.y + 2 + 3 + if show_clock_block { clock_date_height + clock_spacing } else { 0 }
+                + if banner_below { 0 } else { banner_rows };
+            (target.saturating_sub(above as u32)).min(u16::MAX as u32) as u16
+        }
+        None => app.config.ui.top_spacing as u16,
     };
 
+    // Create main layout; optional rows are only added when shown
+    let mut constraints = vec![
+        Constraint::Length(3),                      // Title
+        Constraint::Length(top_spacing),            // Top to clock/fields
+    ];
+    if show_clock_block {
+        constraints.push(Constraint::Length(clock_date_height)); // Clock/Date
+        constraints.push(Constraint::Length(clock_spacing));     // Clock to fields
+    }
+    if banner_rows > 0 && !banner_below {
+        constraints.push(Constraint::Length(banner_rows));
+    }
+    // Input fields in `ui.field_order`, separated by spacing rows
+    let fields = app.form_fields();
+    for (i, &field) in fields.iter().enumerate() {
+        if i > 0 {
+            constraints.push(Constraint::Length(spacing));
+        }
+        // The session gets one extra row for the command preview
+        let extra = field == Focus::Session && app.config.ui.show_session_command && !compact;
+        constraints.push(Constraint::Length(height + extra as u16));
+    }
+    if banner_rows > 0 && banner_below {
+        constraints.push(Constraint::Length(banner_rows));
+    }
+    constraints.push(Constraint::Min(0));           // Error/Space
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(constraints)
+        .split(body);
+
     let mut chunk_idx = 0;
-    draw_title(f, chunks[chunk_idx], &app.config);
+    // `ui.focus_dim`: fade the title and clock while the password is being typed
+    let dim = app.config.ui.focus_dim && app.focus == Focus::Password;
+    draw_title(f, chunks[chunk_idx], &app.config, app.hostname.as_deref(), dim);
     chunk_idx += 1;
     chunk_idx += 1; // skip top spacing
-    if app.config.ui.show_clock || app.config.ui.show_date {
-        draw_clock_date(f, chunks[chunk_idx], &app.config);
+    if show_clock_block {
+        draw_clock_date(f, chunks[chunk_idx], &app.config, last_login, dim);
         chunk_idx += 1;
         chunk_idx += 1; // skip clock spacing
     }
-    draw_username(f, chunks[chunk_idx], app, width);
-    chunk_idx += 1;
-    chunk_idx += 1; // skip spacing
-    draw_password(f, chunks[chunk_idx], app, width);
-    chunk_idx += 1;
-    chunk_idx += 1; // skip spacing
-    draw_session(f, chunks[chunk_idx], app, width);
-    chunk_idx += 1;
-    if let Some(ref error) = app.error_message {
-        draw_error(f, chunks[chunk_idx], error);
+    if let Some(text) = banner.filter(|_| !banner_below) {
+        let area = chunks[chunk_idx];
+        draw_banner(f, Rect { height: area.height.saturating_sub(1), ..area }, text, width, &app.config);
+        chunk_idx += 1;
+    }
+    for (i, &field) in fields.iter().enumerate() {
+        if i > 0 {
+            chunk_idx += 1; // skip spacing
+        }
+        let area = chunks[chunk_idx];
+        match field {
+            Focus::Username => draw_username(f, area, app, width),
+            Focus::Password => draw_password(f, area, app, width),
+            Focus::ExtraSecret => {
+                let label = app.config.ui.extra_secret.as_deref().unwrap_or_default();
+                draw_secret(f, area, app, field, label, &app.extra_secret, width);
+            }
+            Focus::Prompt(i) => {
+                let label = &app.config.security.prompt_form[i];
+                draw_secret(f, area, app, field, label, &app.prompt_answers[i], width);
+            }
+            Focus::Session => draw_session(f, area, app, width),
+        }
+        chunk_idx += 1;
+    }
+    if let Some(text) = banner.filter(|_| banner_below) {
+        let area = chunks[chunk_idx];
+        draw_banner(f, Rect { y: area.y + 1, height: area.height.saturating_sub(1), ..area }, text, width, &app.config);
+        chunk_idx += 1;
+    }
+    // The error/status line first, then PAM's messages below it
+    let mut message_area = chunks[chunk_idx];
+    if !app.pam_messages.is_empty() && message_area.height > 2 {
+        let log_height = (app.pam_messages.len() as u16).min(PAM_LOG_ROWS).min(message_area.height - 2);
+        draw_pam_messages(f, Rect {
+            y: message_area.y + 2,
+            height: log_height,
+            ..message_area
+        }, app);
+        message_area.height = 2;
+    }
+    if let Some(ref welcome) = app.success_message {
+        draw_success(f, message_area, welcome, &app.config);
+    } else if let Some(ref error) = app.error_message {
+        draw_error(f, message_area, error, &app.config);
+    } else if let Some(progress) = auth_progress(app) {
+        draw_status(f, message_area, &progress, &app.config);
+    } else if let Some(ref status) = app.status_message {
+        draw_status(f, message_area, status, &app.config);
+    }
+    draw_widgets(f, body, app);
+    if let Some(ref message) = app.config.ui.footer_message {
+        draw_footer(f, Rect {
+            x: 0,
+            y: (body.y + body.height).saturating_sub(1),
+            width: size.width,
+            height: 1.min(body.height),
+        }, message, &app.config);
+    }
+    if let Some(ref e) = app.config.load_error {
+        draw_config_banner(f, Rect { height: 1.min(body.height), ..body }, e);
+    }
+    if help_row {
+        draw_help(f, app, Rect {
+            x: 0,
+            y: if help_on_top { 0 } else { size.height - 1 },
+            width: size.width,
+            height: 1,
+        });
+    }
+
+    if app.show_debug {
+        draw_debug_overlay(f, size, app);
+    }
+    if let Some(rows) = &app.system_info {
+        draw_system_info(f, size, app, rows);
+    }
+    if let Some((level, _)) = app.brightness {
+        draw_brightness(f, size, app, level);
     }
-    // Always draw help at the bottom of the terminal
-    draw_help(f, Rect {
-        x: 0,
-        y: size.height.saturating_sub(1),
-        width: size.width,
-        height: 1,
-    });
+}
+
+/// Compute the input field width in columns.
+///
+/// `field_width` is a plain percentage of the terminal width (100 = full
+/// width), capped by `max_field_cols` when set. The result never drops below
+/// 20 columns and never exceeds the terminal.
+fn field_width(term_width: u16, ui: &crate::config::UiConfig) -> u16 {
+    let mut cols = term_width as u32 * ui.field_width.min(100) / 100;
+    if let Some(max) = ui.max_field_cols {
+        cols = cols.min(max);
+    }
+    (cols.min(u16::MAX as u32) as u16).clamp(20.min(term_width), term_width)
 }
 
 /// Draw title
-fn draw_title(f: &mut Frame<'_>, area: Rect, config: &Config) {
-    let title = Paragraph::new(config.ui.title.as_str())
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+fn draw_title(f: &mut Frame<'_>, area: Rect, config: &Config, hostname: Option<&str>, dim: bool) {
+    let mut lines = vec![Line::from(config.ui.title.as_str())];
+    if let Some(host) = hostname {
+        lines.push(Line::from(Span::styled(
+            host.to_string(),
+            Style::default().fg(Color::Gray).remove_modifier(Modifier::BOLD),
+        )));
+    }
+    let color = config.ui.colors.title_color().map_or(Color::Cyan, parse_hex_color);
+    let style = Style::default().fg(color).add_modifier(Modifier::BOLD);
+    let title = Paragraph::new(lines)
+        .style(if dim { dimmed(style, config) } else { style })
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::NONE));
     f.render_widget(title, area);
 }
 
+/// "Last login: <time> as <user>", when enabled and recorded
+fn last_login_line(config: &Config) -> Option<String> {
+    if !config.ui.show_last_login {
+        return None;
+    }
+    let time = config.last_login?;
+    let user = config.last_user.as_deref().unwrap_or("?");
+    Some(format!("Last login: {} as {}", time.format("%Y-%m-%d %H:%M"), user))
+}
+
+/// The time-dependent text on screen (clock and date), used to redraw only
+/// when it changes
+pub fn clock_text(config: &Config) -> String {
+    let now = Utc::now();
+    let mut text = String::new();
+    if config.ui.show_clock {
+        text.push_str(&format_time(now, &config.ui, config.ui.effective_clock_format()));
+    }
+    if config.ui.show_date {
+        text.push('\n');
+        text.push_str(&format_time(now, &config.ui, config.ui.effective_date_format()));
+    }
+    text
+}
+
 /// Draw clock and date
-fn draw_clock_date(f: &mut Frame<'_>, area: Rect, config: &Config) {
-    let now = Local::now();
+fn draw_clock_date(f: &mut Frame<'_>, area: Rect, config: &Config, last_login: Option<String>, dim: bool) {
+    let now = Utc::now();
     let mut text = Vec::new();
+    let style = |style: Style| if dim { dimmed(style, config) } else { style };
 
+    let colors = &config.ui.colors;
     if config.ui.show_clock {
-        let clock = now.format(&config.ui.clock_format).to_string();
+        let clock = format_time(now, &config.ui, config.ui.effective_clock_format());
+        let color = colors.clock.as_deref().map_or(Color::White, parse_hex_color);
         text.push(Line::from(vec![
-            Span::styled(clock, Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
+            Span::styled(clock, style(Style::default().fg(color).add_modifier(Modifier::BOLD)))
         ]));
     }
 
     if config.ui.show_date {
-        let date = now.format(&config.ui.date_format).to_string();
+        let date = format_time(now, &config.ui, config.ui.effective_date_format());
+        let color = colors.date.as_deref().map_or(Color::Gray, parse_hex_color);
+        text.push(Line::from(vec![
+            Span::styled(date, style(Style::default().fg(color)))
+        ]));
+    }
+
+    if let Some(last_login) = last_login {
         text.push(Line::from(vec![
-            Span::styled(date, Style::default().fg(Color::Gray))
+            Span::styled(last_login, style(Style::default().fg(Color::DarkGray)))
         ]));
     }
 
@@ -236,40 +1181,230 @@ fn draw_clock_date(f: &mut Frame<'_>, area: Rect, config: &Config) {
 
 /// Draw username field
 fn draw_username(f: &mut Frame<'_>, area: Rect, app: &App, width: u16) {
-    let style = get_field_style(app.focus == Focus::Username, &app.config.ui.colors);
+    if app.config.ui.layout_style == "compact" {
+        let cursor = app.username.chars().count();
+        draw_compact_row(f, area, app, Focus::Username, "Username", &app.username, Some(cursor), width);
+        return;
+    }
+    let style = field_style(app, Focus::Username);
+    let block = field_block(&app.config, style)
+        .title(if app.users.is_empty() { "Username" } else { "Username (\u{2191}/\u{2193})" });
+    let centered = centered_rect(width, area.height, area);
+    let inner = block.inner(centered);
     let username = Paragraph::new(app.username.as_str())
         .style(style)
-        .block(Block::default()
-            .borders(Borders::ALL)
-            .border_style(style)
-            .title("Username"))
+        .block(block)
         .wrap(Wrap { trim: true });
-    let centered = centered_rect(width, area.height, area);
     f.render_widget(username, centered);
+    if app.focus == Focus::Username {
+        place_cursor(f, inner, app.username.chars().count());
+    }
+
+    if app.config.ui.show_avatar && !app.username.is_empty() {
+        draw_avatar(f, centered, &app.username, &app.config);
+    }
+}
+
+/// Avatar background colors, picked per username
+const AVATAR_PALETTE: [Color; 6] = [
+    Color::Rgb(0x7a, 0xa2, 0xf7),
+    Color::Rgb(0x9e, 0xce, 0x6a),
+    Color::Rgb(0xe0, 0xaf, 0x68),
+    Color::Rgb(0xbb, 0x9a, 0xf7),
+    Color::Rgb(0x7d, 0xcf, 0xff),
+    Color::Rgb(0xff, 0x9e, 0x64),
+];
+
+/// Draw the user's initials in a colored block left of the username field
+fn draw_avatar(f: &mut Frame<'_>, field: Rect, username: &str, config: &Config) {
+    let avatar_width = field.height * 2;
+    if field.x < avatar_width + 1 {
+        return;
+    }
+    let area = Rect {
+        x: field.x - avatar_width - 1,
+        y: field.y,
+        width: avatar_width,
+        height: field.height,
+    };
+
+    let initials: String = username
+        .split(['.', '_', '-', ' '])
+        .filter_map(|part| part.chars().next())
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect();
+    let hash = username.bytes().fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+    let color = AVATAR_PALETTE[hash % AVATAR_PALETTE.len()];
+
+    let mut lines = vec![Line::from(""); (area.height.saturating_sub(1) / 2) as usize];
+    lines.push(Line::from(initials));
+    let avatar = Paragraph::new(lines)
+        .style(Style::default()
+            .bg(color)
+            .fg(parse_hex_color(&config.ui.colors.background))
+            .add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center);
+    f.render_widget(avatar, area);
 }
 
 /// Draw password field
 fn draw_password(f: &mut Frame<'_>, area: Rect, app: &App, width: u16) {
-    let style = get_field_style(app.focus == Focus::Password, &app.config.ui.colors);
-    let password_display = if app.config.security.mask_password {
-        "*".repeat(app.password.len())
-    } else {
-        app.password.clone()
+    let password_display = password_display(app);
+    // An empty field submits as-is when the policy allows it; say so
+    let placeholder = (password_display.is_empty() && !app.auth_open && app.config.security.allow_empty_password)
+        .then_some("(no password required)");
+    if app.config.ui.layout_style == "compact" {
+        let cursor = password_display.chars().count();
+        let title = password_label(app);
+        let value = placeholder.unwrap_or(&password_display);
+        draw_compact_row(f, area, app, Focus::Password, &title, value, Some(cursor), width);
+        return;
+    }
+    let style = field_style(app, Focus::Password);
+    let mut block = field_block(&app.config, style)
+        .title(password_label(app));
+    if app.failed_attempts > 0 {
+        block = block.title(
+            Title::from(format!("Attempt {}", app.failed_attempts + 1)).alignment(Alignment::Right),
+        );
+    }
+    if app.autofilled && app.config.ui.show_autofill_hint {
+        block = block.title(
+            Title::from(Span::styled(
+                format!(" logging in as {} ", app.username),
+                Style::default().add_modifier(Modifier::DIM),
+            ))
+            .position(Position::Bottom)
+            .alignment(Alignment::Right),
+        );
+    }
+    let centered = centered_rect(width, area.height, area);
+    let inner = block.inner(centered);
+    let cursor_col = password_display.chars().count();
+    let content = match placeholder {
+        Some(text) => Line::from(Span::styled(text, Style::default().add_modifier(Modifier::DIM))),
+        None if app.password_selected => Line::from(Span::styled(password_display, selection_style(&app.config))),
+        None => Line::from(password_display),
     };
-    let password = Paragraph::new(password_display)
+    let password = Paragraph::new(content)
         .style(style)
-        .block(Block::default()
-            .borders(Borders::ALL)
-            .border_style(style)
-            .title("Password"))
+        .block(block)
         .wrap(Wrap { trim: true });
-    let centered = centered_rect(width, area.height, area);
     f.render_widget(password, centered);
+    if app.focus == Focus::Password {
+        place_cursor(f, inner, cursor_col);
+    }
+}
+
+/// Whether the password shows as asterisks: masking is on and greetd did
+/// not ask for a visible answer
+fn password_masked(app: &App) -> bool {
+    app.config.security.mask_password && !(app.auth_open && app.auth_echo)
+}
+
+/// The password field's title, with the typed length when
+/// `ui.show_password_length` is set and the value is masked
+fn password_label(app: &App) -> String {
+    if app.config.ui.show_password_length && password_masked(app) {
+        format!("{} ({})", app.password_title(), app.password.chars().count())
+    } else {
+        app.password_title().to_string()
+    }
+}
+
+/// What the password field shows: the mask character (`security.mask_char`)
+/// unless unmasked or echoing
+fn password_display(app: &App) -> String {
+    if password_masked(app) {
+        let count = app.password.chars().count();
+        let mask = app.config.security.mask();
+        // `security.reveal_last_char`: the newest character stays readable briefly
+        let revealed = app.config.security.reveal_last_char && app.password_typed_at.is_some();
+        match app.password.chars().last().filter(|_| revealed) {
+            Some(last) => format!("{}{}", mask.repeat(count - 1), last),
+            None => mask.repeat(count),
+        }
+    } else {
+        app.password.clone()
+    }
+}
+
+/// Draw an always-masked field: the extra secret or a prompt form answer
+#[allow(clippy::too_many_arguments)]
+fn draw_secret(f: &mut Frame<'_>, area: Rect, app: &App, field: Focus, label: &str, value: &str, width: u16) {
+    let display = app.config.security.mask().repeat(value.chars().count());
+    if app.config.ui.layout_style == "compact" {
+        let cursor = display.chars().count();
+        draw_compact_row(f, area, app, field, label, &display, Some(cursor), width);
+        return;
+    }
+    let style = field_style(app, field);
+    let block = field_block(&app.config, style).title(label);
+    let centered = centered_rect(width, area.height, area);
+    let inner = block.inner(centered);
+    let cursor_col = display.chars().count();
+    let secret = Paragraph::new(display)
+        .style(style)
+        .block(block)
+        .wrap(Wrap { trim: true });
+    f.render_widget(secret, centered);
+    if app.focus == field {
+        place_cursor(f, inner, cursor_col);
+    }
+}
+
+/// Draw one field of the compact layout as a single `Label: value` row,
+/// with the cursor `cursor` characters into the value when focused
+#[allow(clippy::too_many_arguments)]
+fn draw_compact_row(
+    f: &mut Frame<'_>,
+    area: Rect,
+    app: &App,
+    field: Focus,
+    label: &str,
+    value: &str,
+    cursor: Option<usize>,
+    width: u16,
+) {
+    let style = field_style(app, field);
+    let label = format!("{}: ", label);
+    let row = centered_rect(width, 1.min(area.height), area);
+    let line = Line::from(vec![
+        Span::styled(label.clone(), style.add_modifier(Modifier::BOLD)),
+        Span::styled(value.to_string(), style),
+    ]);
+    f.render_widget(Paragraph::new(line), row);
+    if let Some(col) = cursor.filter(|_| app.focus == field) {
+        place_cursor(f, row, label.chars().count() + col);
+    }
+}
+
+/// Show the text cursor after `col` characters on the first line of a field
+fn place_cursor(f: &mut Frame<'_>, inner: Rect, col: usize) {
+    if inner.width == 0 || inner.height == 0 {
+        return;
+    }
+    let col = (col as u16).min(inner.width - 1);
+    f.set_cursor(inner.x + col, inner.y);
+}
+
+/// Terminal cursor shape for `ui.cursor_style` / `ui.cursor_blink`
+pub fn cursor_style(ui: &crate::config::UiConfig) -> SetCursorStyle {
+    match (ui.cursor_style.as_str(), ui.cursor_blink) {
+        ("block", true) => SetCursorStyle::BlinkingBlock,
+        ("block", false) => SetCursorStyle::SteadyBlock,
+        ("bar", true) => SetCursorStyle::BlinkingBar,
+        ("bar", false) => SetCursorStyle::SteadyBar,
+        ("underline", true) => SetCursorStyle::BlinkingUnderScore,
+        ("underline", false) => SetCursorStyle::SteadyUnderScore,
+        _ => SetCursorStyle::DefaultUserShape,
+    }
 }
 
 /// Draw session selector
 fn draw_session(f: &mut Frame<'_>, area: Rect, app: &App, width: u16) {
-    let style = get_field_style(app.focus == Focus::Session, &app.config.ui.colors);
+    let style = field_style(app, Focus::Session);
 
     let session_text = if app.config.sessions.is_empty() {
         "(no sessions)".to_string()
@@ -279,12 +1414,25 @@ fn draw_session(f: &mut Frame<'_>, area: Rect, app: &App, width: u16) {
         app.config.sessions[app.selected_session].name.clone()
     };
 
-    let session = Paragraph::new(session_text)
+    if app.config.ui.layout_style == "compact" {
+        draw_compact_row(f, area, app, Focus::Session, "Session", &session_text, None, width);
+        return;
+    }
+
+    let mut lines = vec![Line::from(session_text)];
+    if app.config.ui.show_session_command {
+        if let Some(session) = app.current_session() {
+            lines.push(Line::from(Span::styled(
+                session.command.clone(),
+                Style::default().add_modifier(Modifier::DIM),
+            )));
+        }
+    }
+
+    let session = Paragraph::new(lines)
         .style(style)
         .alignment(Alignment::Center)
-        .block(Block::default()
-            .borders(Borders::ALL)
-            .border_style(style)
+        .block(field_block(&app.config, style)
             .title("Session"))
         .wrap(Wrap { trim: true });
     let centered = centered_rect(width, area.height, area);
@@ -292,18 +1440,274 @@ fn draw_session(f: &mut Frame<'_>, area: Rect, app: &App, width: u16) {
 }
 
 /// Draw error message
-fn draw_error(f: &mut Frame<'_>, area: Rect, error: &str) {
-    let error_widget = Paragraph::new(error)
-        .style(Style::default().fg(Color::Red))
+fn draw_error(f: &mut Frame<'_>, area: Rect, error: &str, config: &Config) {
+    let error_widget = Paragraph::new(format!("\u{26a0} {}", error))
+        .style(Style::default().fg(parse_hex_color(&config.ui.colors.error)))
         .alignment(Alignment::Center)
         .wrap(Wrap { trim: true });
     f.render_widget(error_widget, area);
 }
 
-/// Draw help text
-fn draw_help(f: &mut Frame<'_>, area: Rect) {
-    let help_text =
-        "Tab: Next Field | Shift+Tab: Previous Field | \u{2190}/\u{2192}: Change Session | Enter: Login | Esc: Exit";
+/// Spinner frames of the login progress line
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// The progress line while greetd is slow to answer: a spinner, escalated
+/// after `ui.slow_auth_secs` to say the wait is probably the auth server
+pub fn auth_progress(app: &App) -> Option<String> {
+    let elapsed = app.auth_started?.elapsed();
+    let frame = SPINNER[(elapsed.as_millis() / 150) as usize % SPINNER.len()];
+    Some(if elapsed >= Duration::from_secs(app.config.ui.slow_auth_secs) {
+        format!("{} Still working\u{2026} (contacting auth server)", frame)
+    } else {
+        format!("{} Authenticating\u{2026}", frame)
+    })
+}
+
+/// How long the brightness bar stays up after the last change
+const BRIGHTNESS_BAR_DURATION: Duration = Duration::from_millis(1500);
+
+/// How long `security.reveal_last_char` leaves the newest character readable
+const REVEAL_DURATION: Duration = Duration::from_millis(1000);
+
+/// Most PAM messages shown at once; older ones are reached with PageUp
+const PAM_LOG_ROWS: u16 = 5;
+
+/// Draw the newest PAM messages that fit (less `pam_scroll`), one per row
+fn draw_pam_messages(f: &mut Frame<'_>, area: Rect, app: &App) {
+    let end = app.pam_messages.len() - app.pam_scroll.min(app.pam_messages.len());
+    let start = end.saturating_sub(area.height as usize);
+    let lines: Vec<Line> = app.pam_messages[start..end]
+        .iter()
+        .map(|(level, message)| {
+            let color = match level {
+                MessageLevel::Info => &app.config.ui.colors.foreground,
+                MessageLevel::Error => &app.config.ui.colors.error,
+            };
+            Line::from(Span::styled(message.as_str(), Style::default().fg(parse_hex_color(color))))
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines).alignment(Alignment::Center), area);
+}
+
+/// Draw the welcome message after a successful login
+fn draw_success(f: &mut Frame<'_>, area: Rect, message: &str, config: &Config) {
+    let success_widget = Paragraph::new(message)
+        .style(Style::default()
+            .fg(parse_hex_color(&config.ui.colors.success))
+            .add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(success_widget, area);
+}
+
+/// Draw status message
+fn draw_status(f: &mut Frame<'_>, area: Rect, status: &str, config: &Config) {
+    let status_widget = Paragraph::new(status)
+        .style(Style::default().fg(parse_hex_color(&config.ui.colors.foreground)))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(status_widget, area);
+}
+
+/// Replace the whole form with the required terminal size
+fn draw_too_small(f: &mut Frame<'_>, size: Rect, min_cols: u16, min_rows: u16, config: &Config) {
+    let message = Paragraph::new(format!("Terminal too small (need {}x{})", min_cols, min_rows))
+        .style(Style::default()
+            .fg(parse_hex_color(&config.ui.colors.foreground))
+            .bg(parse_hex_color(&config.ui.colors.background)))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    let row = Rect { y: size.y + size.height / 2, height: size.height - size.height / 2, ..size };
+    f.render_widget(Block::default().style(Style::default().bg(parse_hex_color(&config.ui.colors.background))), size);
+    f.render_widget(message, row);
+}
+
+/// Draw the warning that the config failed to load and defaults are in use
+fn draw_config_banner(f: &mut Frame<'_>, area: Rect, error: &str) {
+    let banner = Paragraph::new(format!("Using default config (config error: {})", error))
+        .style(Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center);
+    f.render_widget(banner, area);
+}
+
+/// Draw the `ui.widgets`, stacked from the edge inwards in their corner
+fn draw_widgets(f: &mut Frame<'_>, area: Rect, app: &App) {
+    let mut used = [0u16; 4];
+    for (index, widget) in app.config.ui.widgets.iter().enumerate() {
+        let (slot, top, alignment) = match widget.corner.to_lowercase().as_str() {
+            "tl" => (0, true, Alignment::Left),
+            "tr" => (1, true, Alignment::Right),
+            "bl" => (2, false, Alignment::Left),
+            "br" => (3, false, Alignment::Right),
+            _ => continue,
+        };
+        if used[slot] >= area.height {
+            continue;
+        }
+        let y = if top {
+            area.y + used[slot]
+        } else {
+            area.y + area.height - 1 - used[slot]
+        };
+        used[slot] += 1;
+        let text = crate::widgets::text(widget, index, &app.widget_outputs, &app.config);
+        let item = Paragraph::new(text)
+            .style(Style::default().fg(parse_hex_color(&app.config.ui.colors.foreground)))
+            .alignment(alignment);
+        f.render_widget(item, Rect { y, height: 1, ..area });
+    }
+}
+
+/// Draw footer message
+fn draw_footer(f: &mut Frame<'_>, area: Rect, message: &str, config: &Config) {
+    let footer = Paragraph::new(message)
+        .style(Style::default().fg(parse_hex_color(&config.ui.colors.foreground)))
+        .alignment(Alignment::Center);
+    f.render_widget(footer, area);
+}
+
+/// Draw the `ui.banner_file` text wrapped to the field width
+fn draw_banner(f: &mut Frame<'_>, area: Rect, text: &str, width: u16, config: &Config) {
+    let banner = Paragraph::new(text)
+        .style(Style::default().fg(parse_hex_color(&config.ui.colors.foreground)))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+    f.render_widget(banner, centered_rect(width, area.height, area));
+}
+
+/// Rows `text` takes when wrapped to `width` columns
+fn wrapped_rows(text: &str, width: u16) -> u16 {
+    let width = width.max(1) as usize;
+    text.lines()
+        .map(|line| UnicodeWidthStr::width(line).div_ceil(width).max(1))
+        .sum::<usize>()
+        .min(u16::MAX as usize) as u16
+}
+
+/// Draw the launch preview: exactly what `start_session` would send
+fn draw_debug_overlay(f: &mut Frame<'_>, size: Rect, app: &App) {
+    let mut lines = Vec::new();
+    match app.current_session() {
+        Some(session) => {
+            lines.push(Line::from(format!("Session: {}", session.name)));
+            lines.push(Line::from(format!(
+                "Type:    {}",
+                if session.shell { "shell (/bin/sh -lc)" } else { "exec" }
+            )));
+            match crate::session::build_argv(session, &app.config.session) {
+                Ok(argv) => {
+                    lines.push(Line::from("argv:"));
+                    for (i, arg) in argv.iter().enumerate() {
+                        lines.push(Line::from(format!("  [{}] {:?}", i, arg)));
+                    }
+                }
+                Err(e) => lines.push(Line::from(format!("argv:    error: {}", e))),
+            }
+            let home = crate::users::home_dir(app.login_name());
+            let env = crate::session::build_env(&app.config, Some(session), home.as_deref());
+            if env.is_empty() {
+                lines.push(Line::from("env:     (none, greetd defaults)"));
+            } else {
+                lines.push(Line::from("env:"));
+                lines.extend(env.into_iter().map(|var| Line::from(format!("  {}", var))));
+            }
+        }
+        None => lines.push(Line::from(NO_SESSIONS)),
+    }
+    let target = crate::greetd_client::socket_target(app.config.greetd_socket.as_deref());
+    lines.push(Line::from(format!(
+        "greetd:  {}",
+        crate::greetd_client::protocol_summary(&target)
+    )));
+
+    let width = (size.width * 3 / 4).max(20).min(size.width);
+    // Long values (argv, PATH) wrap onto several rows
+    let inner_width = width.saturating_sub(2).max(1) as usize;
+    let rows: usize = lines.iter().map(|l| l.width().max(1).div_ceil(inner_width)).sum();
+    let height = (rows as u16 + 2).min(size.height);
+    let area = centered_rect_both(width, height, size);
+    let overlay = Paragraph::new(lines)
+        .style(Style::default()
+            .fg(parse_hex_color(&app.config.ui.colors.foreground))
+            .bg(parse_hex_color(&app.config.ui.colors.background)))
+        .block(field_block(&app.config, Style::default())
+            .title("Launch preview (F12 to close)"))
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, area);
+    f.render_widget(overlay, area);
+}
+
+/// Draw the brightness bar centered near the bottom of the screen
+fn draw_brightness(f: &mut Frame<'_>, size: Rect, app: &App, level: u8) {
+    const SLOTS: usize = 20;
+    let filled = level as usize * SLOTS / 100;
+    let text = format!(
+        "Brightness [{}{}] {:>3}%",
+        "#".repeat(filled),
+        "-".repeat(SLOTS - filled),
+        level
+    );
+    let width = (text.chars().count() as u16 + 4).min(size.width);
+    let area = Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + size.height.saturating_sub(6),
+        width,
+        height: 3.min(size.height),
+    };
+    let bar = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .style(Style::default()
+            .fg(parse_hex_color(&app.config.ui.colors.foreground))
+            .bg(parse_hex_color(&app.config.ui.colors.background)))
+        .block(field_block(&app.config, Style::default()));
+    f.render_widget(Clear, area);
+    f.render_widget(bar, area);
+}
+
+/// Draw the read-only system info panel
+fn draw_system_info(f: &mut Frame<'_>, size: Rect, app: &App, rows: &[(&str, String)]) {
+    let lines: Vec<Line> = rows
+        .iter()
+        .map(|(label, value)| Line::from(format!("{:<8} {}", format!("{}:", label), value)))
+        .collect();
+    let width = (size.width * 3 / 4).max(20).min(size.width);
+    let height = (lines.len() as u16 + 2).min(size.height);
+    let area = centered_rect_both(width, height, size);
+    let panel = Paragraph::new(lines)
+        .style(Style::default()
+            .fg(parse_hex_color(&app.config.ui.colors.foreground))
+            .bg(parse_hex_color(&app.config.ui.colors.background)))
+        .block(field_block(&app.config, Style::default()).title(if app.config.ui.esc_closes_overlay() {
+            "System info (Esc to close)"
+        } else {
+            "System info (F9 to close)"
+        }))
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, area);
+    f.render_widget(panel, area);
+}
+
+/// Draw help text, listing only the keys that do something here
+fn draw_help(f: &mut Frame<'_>, app: &App, area: Rect) {
+    let config = &app.config;
+    let mut keys = vec!["Tab: Next Field", "Shift+Tab: Previous Field"];
+    if config.ui.show_session && config.sessions.len() > 1 {
+        keys.push("\u{2190}/\u{2192}: Change Session");
+        keys.push("Alt+1-9: Pick Session");
+    }
+    keys.push("Enter: Login");
+    match config.ui.esc_action.as_str() {
+        "clear_field" => keys.push("Esc: Clear Field"),
+        "clear_all" => keys.push("Esc: Clear All"),
+        _ => {}
+    }
+    if PowerAction::Poweroff.is_configured(&config.power) {
+        keys.push("F1: Power Off");
+    }
+    if PowerAction::Reboot.is_configured(&config.power) {
+        keys.push("F2: Reboot");
+    }
+    let help_text = keys.join(" | ");
 
     let help = Paragraph::new(help_text)
         .style(Style::default().fg(Color::DarkGray))
@@ -311,6 +1715,16 @@ fn draw_help(f: &mut Frame<'_>, area: Rect) {
     f.render_widget(help, area);
 }
 
+/// Highlight for selected text: `ui.colors.selection` (or the accent) behind
+/// the background color, else reverse video
+fn selection_style(config: &Config) -> Style {
+    let colors = &config.ui.colors;
+    match colors.selection_color() {
+        Some(color) => Style::default().fg(parse_hex_color(&colors.background)).bg(parse_hex_color(color)),
+        None => Style::default().add_modifier(Modifier::REVERSED),
+    }
+}
+
 /// Get style for input fields based on focus and color scheme
 fn get_field_style(focused: bool, colors: &crate::config::ColorScheme) -> Style {
     if focused {
@@ -320,7 +1734,70 @@ fn get_field_style(focused: bool, colors: &crate::config::ColorScheme) -> Style
     }
 }
 
-/// Helper to center a rect of given width/height in parent area
+/// Bordered block for an input field, using the configured `ui.border_style`
+fn field_block<'a>(config: &Config, style: Style) -> Block<'a> {
+    let border_type = match config.ui.border_style.as_str() {
+        "none" => return Block::default().borders(Borders::NONE).border_style(style),
+        "rounded" => BorderType::Rounded,
+        "double" => BorderType::Double,
+        "thick" => BorderType::Thick,
+        _ => BorderType::Plain,
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .border_type(border_type)
+        .border_style(style)
+}
+
+/// Style for a field, fading the focused color in while its animation runs
+fn field_style(app: &App, field: Focus) -> Style {
+    if field == Focus::Username && app.config.locked_user().is_some() {
+        return Style::default()
+            .fg(parse_hex_color(&app.config.ui.colors.foreground))
+            .add_modifier(Modifier::DIM);
+    }
+    let focused = app.focus == field;
+    let phase = app.focus_phase[field.index()];
+    if !app.config.ui.animations || !focused || phase >= FOCUS_ANIM_TICKS {
+        return get_field_style(focused, &app.config.ui.colors);
+    }
+    let from = parse_hex_color(&app.config.ui.colors.foreground);
+    let to = parse_hex_color(&app.config.ui.colors.focused);
+    Style::default()
+        .fg(blend_color(from, to, phase as f32 / FOCUS_ANIM_TICKS as f32))
+        .add_modifier(Modifier::BOLD)
+}
+
+/// Fade a style halfway toward the background, or dim it if it is not RGB
+fn dimmed(style: Style, config: &Config) -> Style {
+    let bg = parse_hex_color(&config.ui.colors.background);
+    match style.fg {
+        Some(fg @ Color::Rgb(..)) => style.fg(blend_color(fg, bg, 0.5)),
+        Some(fg) => style.fg(fg).add_modifier(Modifier::DIM),
+        None => style.add_modifier(Modifier::DIM),
+    }
+}
+
+/// Linear blend between two RGB colors; non-RGB colors snap to `to`
+fn blend_color(from: Color, to: Color, t: f32) -> Color {
+    match (from, to) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+            Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+        }
+        _ => to,
+    }
+}
+
+/// Helper to center a rect of given width/height in parent area, on both axes.
+/// Used for overlay modals.
+fn centered_rect_both(width: u16, height: u16, area: Rect) -> Rect {
+    let rect = centered_rect(width, height, area);
+    Rect { y: area.y + (area.height - rect.height) / 2, ..rect }
+}
+
+/// Helper to center a rect of given width/height in parent area (horizontally;
+/// the rect keeps the parent's top edge)
 fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let x = area.x + (area.width.saturating_sub(width)) / 2;
     let y = area.y;
@@ -0,0 +1,59 @@
+use crate::config::UiConfig;
+use std::path::PathBuf;
+
+/// UID range of regular login accounts on most distributions
+const MIN_UID: u32 = 1000;
+const MAX_UID: u32 = 60000;
+
+/// Load the users offered by `ui.user_list` from `/etc/passwd`.
+pub fn load_login_users(ui: &UiConfig) -> Vec<String> {
+    let passwd = std::fs::read_to_string("/etc/passwd").unwrap_or_default();
+    let shells = std::fs::read_to_string("/etc/shells").ok();
+    filter_login_users(&passwd, shells.as_deref(), ui)
+}
+
+/// Pick login users out of passwd-formatted content: UID in the normal
+/// range, a real login shell (listed in `shells` when given), then
+/// `user_list_include` (if non-empty) and `user_list_exclude` applied.
+pub fn filter_login_users(passwd: &str, shells: Option<&str>, ui: &UiConfig) -> Vec<String> {
+    let valid_shells: Option<Vec<&str>> = shells.map(|s| {
+        s.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect()
+    });
+
+    passwd
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            let (name, uid, shell) = (fields[0], fields[2].parse::<u32>().ok()?, fields[6]);
+            if !(MIN_UID..=MAX_UID).contains(&uid) {
+                return None;
+            }
+            if shell.ends_with("nologin") || shell.ends_with("false") {
+                return None;
+            }
+            if let Some(ref valid) = valid_shells {
+                if !valid.contains(&shell) {
+                    return None;
+                }
+            }
+            Some(name.to_string())
+        })
+        .filter(|name| ui.user_list_include.is_empty() || ui.user_list_include.contains(name))
+        .filter(|name| !ui.user_list_exclude.contains(name))
+        .collect()
+}
+
+/// Home directory of `username` according to `/etc/passwd`
+pub fn home_dir(username: &str) -> Option<PathBuf> {
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split(':').collect();
+        (fields.len() >= 7 && fields[0] == username).then(|| PathBuf::from(fields[5]))
+    })
+}
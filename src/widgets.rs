@@ -0,0 +1,113 @@
+//! `ui.widgets`: small status items in the screen corners. Static text and
+//! built-in sources are read when drawing; commands run on background
+//! threads so a slow one never stalls the greeter.
+
+use crate::config::{Config, WidgetConfig};
+use crate::session::split_words;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+/// Latest output of every command widget
+#[derive(Default)]
+pub struct CommandOutputs {
+    slots: Vec<Slot>,
+}
+
+struct Slot {
+    output: String,
+    next_run: Instant,
+    running: Option<Receiver<String>>,
+}
+
+impl CommandOutputs {
+    /// Collect finished commands and start the ones that are due; returns
+    /// whether any output changed
+    pub fn poll(&mut self, widgets: &[WidgetConfig]) -> bool {
+        if self.slots.len() != widgets.len() {
+            let now = Instant::now();
+            self.slots = widgets
+                .iter()
+                .map(|_| Slot { output: String::new(), next_run: now, running: None })
+                .collect();
+        }
+        let mut changed = false;
+        for (slot, widget) in self.slots.iter_mut().zip(widgets) {
+            let Some(command) = widget.command.as_deref() else {
+                continue;
+            };
+            if let Some(output) = slot.running.as_ref().and_then(|rx| rx.try_recv().ok()) {
+                slot.running = None;
+                changed |= output != slot.output;
+                slot.output = output;
+            }
+            if slot.running.is_none() && Instant::now() >= slot.next_run {
+                slot.running = run(command);
+                slot.next_run = Instant::now() + Duration::from_secs(widget.refresh_secs.max(1));
+            }
+        }
+        changed
+    }
+
+    /// Output of the command widget at `index`, empty until it first finished
+    pub fn get(&self, index: usize) -> &str {
+        self.slots.get(index).map_or("", |slot| slot.output.as_str())
+    }
+}
+
+/// Run `command` on a thread; its first stdout line arrives on the receiver
+fn run(command: &str) -> Option<Receiver<String>> {
+    let argv = split_words(command).ok().filter(|argv| !argv.is_empty())?;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let output = Command::new(&argv[0])
+            .args(&argv[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .map(|out| String::from_utf8_lossy(&out.stdout).lines().next().unwrap_or("").trim().to_string())
+            .unwrap_or_else(|e| format!("{}: {}", argv[0], e));
+        let _ = tx.send(output);
+    });
+    Some(rx)
+}
+
+/// What widget `index` shows: its text, command output or built-in value
+pub fn text(widget: &WidgetConfig, index: usize, outputs: &CommandOutputs, config: &Config) -> String {
+    if let Some(ref text) = widget.text {
+        return text.clone();
+    }
+    if widget.command.is_some() {
+        return outputs.get(index).to_string();
+    }
+    match widget.builtin.as_deref() {
+        Some("clock") => crate::ui::clock_text(config),
+        Some("hostname") => crate::ui::read_hostname().unwrap_or_default(),
+        Some("battery") => battery().unwrap_or_default(),
+        Some("uptime") => uptime().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Charge of the first battery, e.g. "BAT 87%"
+fn battery() -> Option<String> {
+    let mut batteries: Vec<_> = std::fs::read_dir("/sys/class/power_supply")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.file_name().is_some_and(|n| n.to_string_lossy().starts_with("BAT")))
+        .collect();
+    batteries.sort();
+    let capacity = std::fs::read_to_string(batteries.first()?.join("capacity")).ok()?;
+    Some(format!("BAT {}%", capacity.trim()))
+}
+
+/// Time since boot, e.g. "up 3d 4h 12m"
+pub(crate) fn uptime() -> Option<String> {
+    let content = std::fs::read_to_string("/proc/uptime").ok()?;
+    let secs = content.split_whitespace().next()?.parse::<f64>().ok()? as u64;
+    let (days, hours, minutes) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    Some(match days {
+        0 => format!("up {}h {}m", hours, minutes),
+        _ => format!("up {}d {}h {}m", days, hours, minutes),
+    })
+}
@@ -0,0 +1,10 @@
+use hypr_greeter::auth::is_username_prompt;
+
+#[test]
+fn only_plain_login_prompts_are_answered_with_the_username() {
+    assert!(is_username_prompt("login:"));
+    assert!(is_username_prompt(" Username: "));
+    assert!(is_username_prompt("User name:"));
+    assert!(!is_username_prompt("OTP for user alice:"));
+    assert!(!is_username_prompt("Login token:"));
+}
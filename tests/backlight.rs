@@ -0,0 +1,34 @@
+use hypr_greeter::backlight::adjust_device;
+
+#[test]
+fn adjusts_within_the_device_range() {
+    let device = std::env::temp_dir().join(format!("hypr-greeter-backlight-{}", std::process::id()));
+    std::fs::create_dir_all(&device).unwrap();
+    std::fs::write(device.join("max_brightness"), "200\n").unwrap();
+    std::fs::write(device.join("brightness"), "100\n").unwrap();
+
+    assert_eq!(adjust_device(&device, 10), Ok(60));
+    assert_eq!(std::fs::read_to_string(device.join("brightness")).unwrap(), "120");
+    assert_eq!(adjust_device(&device, 100), Ok(100));
+    // Never fully dark
+    assert_eq!(adjust_device(&device, -100), Ok(0));
+    assert_eq!(std::fs::read_to_string(device.join("brightness")).unwrap(), "1");
+
+    std::fs::remove_file(device.join("max_brightness")).unwrap();
+    assert!(adjust_device(&device, 10).unwrap_err().contains("max_brightness"));
+    std::fs::remove_dir_all(&device).unwrap();
+}
+
+#[test]
+fn small_ranges_still_step() {
+    let device = std::env::temp_dir().join(format!("hypr-greeter-backlight-small-{}", std::process::id()));
+    std::fs::create_dir_all(&device).unwrap();
+    std::fs::write(device.join("max_brightness"), "7\n").unwrap();
+    std::fs::write(device.join("brightness"), "3\n").unwrap();
+
+    assert_eq!(adjust_device(&device, 10), Ok(57));
+    assert_eq!(std::fs::read_to_string(device.join("brightness")).unwrap(), "4");
+    assert_eq!(adjust_device(&device, -10), Ok(42));
+    assert_eq!(std::fs::read_to_string(device.join("brightness")).unwrap(), "3");
+    std::fs::remove_dir_all(&device).unwrap();
+}
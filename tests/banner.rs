@@ -0,0 +1,22 @@
+use hypr_greeter::banner::Banner;
+use std::time::{Duration, SystemTime};
+
+#[test]
+fn banner_reloads_when_the_file_changes() {
+    let path = std::env::temp_dir().join(format!("hypr-greeter-banner-{}", std::process::id()));
+    std::fs::write(&path, "Authorized use only.\n").unwrap();
+    let mut banner = Banner::new(&path);
+    assert_eq!(banner.text(), "Authorized use only.");
+    assert!(!banner.refresh());
+
+    std::fs::write(&path, "Maintenance tonight at 22:00\n").unwrap();
+    // Filesystem timestamps can be coarse; make the change visible
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+    assert!(banner.refresh());
+    assert_eq!(banner.text(), "Maintenance tonight at 22:00");
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(banner.refresh());
+    assert_eq!(banner.text(), "");
+}
@@ -0,0 +1,181 @@
+use hypr_greeter::config::load_layers;
+use std::path::PathBuf;
+
+fn write_layer(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("hypr-greeter-{}-{}.toml", std::process::id(), name));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn later_layers_override_individual_fields() {
+    let base = write_layer("base", "[ui]\ntitle = \"Lab\"\nfield_width = 40\n[ui.colors]\nfocused = \"#ff0000\"\n");
+    let user = write_layer("user", "[ui.colors]\nfocused = \"#00ff00\"\n");
+
+    let config = load_layers(&[base.clone(), user.clone()]).unwrap();
+    assert_eq!(config.ui.title, "Lab");
+    assert_eq!(config.ui.field_width, 40);
+    assert_eq!(config.ui.colors.focused, "#00ff00");
+    // Untouched fields keep their defaults
+    assert_eq!(config.ui.colors.background, "#1a1b26");
+
+    let _ = std::fs::remove_file(base);
+    let _ = std::fs::remove_file(user);
+}
+
+#[test]
+fn arrays_replace_and_missing_layers_are_skipped() {
+    let base = write_layer("sessions", "[[sessions]]\nname = \"Only\"\ncommand = \"only\"\n");
+    let missing = std::env::temp_dir().join("hypr-greeter-does-not-exist.toml");
+
+    let config = load_layers(&[base.clone(), missing]).unwrap();
+    assert_eq!(config.sessions.len(), 1);
+    assert_eq!(config.sessions[0].name, "Only");
+
+    let _ = std::fs::remove_file(base);
+}
+
+/// Walk a serialized config and check every key is described by the schema
+fn assert_in_schema(value: &serde_json::Value, schema: &serde_json::Value, root: &serde_json::Value, path: &str) {
+    let schema = match schema.get("$ref").and_then(|r| r.as_str()) {
+        Some(r) => root.pointer(r.trim_start_matches('#')).unwrap(),
+        None => schema,
+    };
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                let child_schema = schema
+                    .get("properties")
+                    .and_then(|p| p.get(key))
+                    .unwrap_or_else(|| panic!("{}.{} missing from config.schema.json", path, key));
+                assert_in_schema(child, child_schema, root, &format!("{}.{}", path, key));
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                assert_in_schema(item, &schema["items"], root, path);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn schema_covers_every_config_field() {
+    let schema: serde_json::Value = serde_json::from_str(hypr_greeter::config::CONFIG_SCHEMA).unwrap();
+    let mut config = hypr_greeter::config::Config::default();
+    config.monitors.push(toml::from_str("name = \"DP-1\"").unwrap());
+
+    let value = serde_json::to_value(&config).unwrap();
+    assert_in_schema(&value, &schema, &schema, "config");
+}
+
+#[test]
+fn field_order_must_list_shown_fields_once() {
+    let mut ui = hypr_greeter::config::Config::default().ui;
+    assert!(ui.check_field_order().is_ok());
+
+    ui.field_order = ["password", "session"].map(String::from).to_vec();
+    assert!(ui.check_field_order().unwrap_err().contains("username"));
+
+    ui.field_order = ["username", "password", "session", "password"].map(String::from).to_vec();
+    assert!(ui.check_field_order().unwrap_err().contains("twice"));
+
+    ui.field_order = ["username", "password", "avatar"].map(String::from).to_vec();
+    assert!(ui.check_field_order().is_err());
+
+    ui.show_session = false;
+    ui.field_order = ["password", "username"].map(String::from).to_vec();
+    assert!(ui.check_field_order().is_ok());
+    assert_eq!(ui.visible_fields(), vec!["password", "username"]);
+}
+
+#[test]
+fn colors_from_wal_palette_override_colors() {
+    let palette = std::env::temp_dir().join(format!("hypr-greeter-wal-{}.json", std::process::id()));
+    std::fs::write(
+        &palette,
+        r##"{"special": {"background": "#101010", "foreground": "#eeeeee", "cursor": "#eeeeee"},
+            "colors": {"color1": "#aa0000", "color2": "#00aa00", "color4": "#0000aa"}}"##,
+    )
+    .unwrap();
+    let layer = write_layer("wal", &format!("[ui]\ncolors_from = {:?}\n", palette.display().to_string()));
+    let config = load_layers(std::slice::from_ref(&layer)).unwrap();
+    std::fs::remove_file(&layer).unwrap();
+
+    assert_eq!(config.ui.colors.background, "#101010");
+    assert_eq!(config.ui.colors.focused, "#0000aa");
+    assert_eq!(config.ui.colors.success, "#00aa00");
+
+    // An explicit accent wins over the palette's focus color
+    let layer = write_layer(
+        "wal-accent",
+        &format!("[ui]\ncolors_from = {:?}\n[ui.colors]\naccent = \"#ff8800\"\n", palette.display().to_string()),
+    );
+    let config = load_layers(std::slice::from_ref(&layer)).unwrap();
+    std::fs::remove_file(&layer).unwrap();
+    assert_eq!(config.ui.colors.focused, "#ff8800");
+    assert_eq!(config.ui.colors.background, "#101010");
+    std::fs::remove_file(&palette).unwrap();
+
+    // A missing palette keeps the configured colors
+    let layer = write_layer("wal-missing", "[ui]\ncolors_from = \"/nonexistent/colors.json\"\n");
+    let config = load_layers(std::slice::from_ref(&layer)).unwrap();
+    std::fs::remove_file(&layer).unwrap();
+    assert_eq!(config.ui.colors.background, "#1a1b26");
+}
+
+#[test]
+fn mask_char_falls_back_to_asterisk_unless_one_column() {
+    let mut security = hypr_greeter::config::Config::default().security;
+    assert_eq!(security.mask(), "*");
+    for (mask, expected) in [("•", "•"), (" ", " "), ("●●", "*"), ("全", "*"), ("", "*")] {
+        security.mask_char = Some(mask.to_string());
+        assert_eq!(security.mask(), expected, "mask_char = {:?}", mask);
+    }
+}
+
+#[test]
+fn accent_fills_in_unset_colors() {
+    let layer = write_layer("accent", "[ui.colors]\naccent = \"#7aa2f7\"\ntitle = \"#ffffff\"\n");
+    let config = load_layers(std::slice::from_ref(&layer)).unwrap();
+    assert_eq!(config.ui.colors.focused, "#7aa2f7");
+    assert_eq!(config.ui.colors.title_color(), Some("#ffffff"));
+    assert_eq!(config.ui.colors.selection_color(), Some("#7aa2f7"));
+
+    std::fs::write(&layer, "[ui.colors]\naccent = \"#7aa2f7\"\nfocused = \"#ff0000\"\n").unwrap();
+    let config = load_layers(std::slice::from_ref(&layer)).unwrap();
+    std::fs::remove_file(&layer).unwrap();
+    assert_eq!(config.ui.colors.focused, "#ff0000");
+    assert_eq!(config.ui.colors.title_color(), Some("#7aa2f7"));
+}
+
+#[test]
+fn config_report_names_the_path_and_state_merge() {
+    use hypr_greeter::config::{config_path, config_report, Config, STATE_FILE};
+    let mut config = Config::default();
+    let report = config_report(&config);
+    assert!(report.contains("/etc/hypr-greeter/config.toml"));
+    assert!(report.contains(&format!("config path: {}", config_path().display())));
+    assert!(report.contains(&format!("state file {}: not merged", STATE_FILE)));
+
+    config.state_loaded = true;
+    config.last_user = Some("alice".to_string());
+    assert!(config_report(&config).contains("merged (last user \"alice\")"));
+}
+
+#[test]
+fn misspelled_choices_are_config_errors() {
+    let layer = write_layer("choices", "[security]\nidle_action = \"suspnd\"\n");
+    let err = load_layers(std::slice::from_ref(&layer)).unwrap_err().to_string();
+    assert!(err.contains("security.idle_action"), "{}", err);
+
+    let layer = write_layer("choices-keep", "[security]\non_error_password = \"keep \"\n");
+    assert!(load_layers(std::slice::from_ref(&layer)).is_err());
+
+    let layer = write_layer("choices-ok", "[ui]\nesc_action = \"clear_all\"\n[[ui.widgets]]\ncorner = \"TR\"\ntext = \"x\"\n");
+    assert!(load_layers(std::slice::from_ref(&layer)).is_ok());
+
+    let example = std::path::PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/config.example.toml"));
+    assert!(load_layers(&[example]).is_ok());
+}
@@ -0,0 +1,235 @@
+use greetd_ipc::{AuthMessageType, ErrorType, Request, Response};
+use hypr_greeter::greetd_client::{self, GreetdClient, GreetdError};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::task::JoinHandle;
+
+/// `GREETD_SOCK` is process-wide, so tests that point it at a mock take turns
+static SOCKET_ENV: Mutex<()> = Mutex::new(());
+
+fn socket_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("hypr-greeter-{}-{}.sock", std::process::id(), name))
+}
+
+async fn read_request(stream: &mut UnixStream) -> Option<Request> {
+    let mut len_buf = [0u8; 4];
+    stream.read_exact(&mut len_buf).await.ok()?;
+    let mut msg = vec![0u8; u32::from_ne_bytes(len_buf) as usize];
+    stream.read_exact(&mut msg).await.ok()?;
+    Some(serde_json::from_slice(&msg).unwrap())
+}
+
+async fn write_response(stream: &mut UnixStream, response: &Response) {
+    let msg = serde_json::to_vec(response).unwrap();
+    stream.write_all(&(msg.len() as u32).to_ne_bytes()).await.unwrap();
+    stream.write_all(&msg).await.unwrap();
+}
+
+/// Serve one connection, answering each request with the next scripted
+/// response (`StartSession` is never answered, like the real daemon), and
+/// return every request that was received.
+fn mock_greetd(path: &PathBuf, script: Vec<Response>) -> JoinHandle<Vec<Request>> {
+    let _ = std::fs::remove_file(path);
+    let listener = UnixListener::bind(path).unwrap();
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut script = script.into_iter();
+        let mut received = Vec::new();
+        while let Some(request) = read_request(&mut stream).await {
+            let answer = !matches!(request, Request::StartSession { .. });
+            received.push(request);
+            if answer {
+                match script.next() {
+                    Some(response) => write_response(&mut stream, &response).await,
+                    None => break,
+                }
+            }
+        }
+        received
+    })
+}
+
+fn with_mock<F, Fut, T>(name: &str, script: Vec<Response>, test: F) -> (T, Vec<Request>)
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = T>,
+{
+    let _guard = SOCKET_ENV.lock().unwrap_or_else(|e| e.into_inner());
+    let path = socket_path(name);
+    std::env::set_var("GREETD_SOCK", &path);
+
+    let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    let (result, received) = rt.block_on(async {
+        let server = mock_greetd(&path, script);
+        let result = test().await;
+        (result, server.await.unwrap())
+    });
+
+    let _ = std::fs::remove_file(&path);
+    (result, received)
+}
+
+fn secret_prompt() -> Response {
+    Response::AuthMessage {
+        auth_message_type: AuthMessageType::Secret,
+        auth_message: "Password:".to_string(),
+    }
+}
+
+#[test]
+fn login_succeeds_and_starts_session() {
+    let (result, received) = with_mock(
+        "login-ok",
+        vec![secret_prompt(), Response::Success],
+        || greetd_client::login("alice", "hunter2", vec!["sway".to_string()]),
+    );
+
+    assert!(result.is_ok());
+    assert!(matches!(&received[0], Request::CreateSession { username } if username == "alice"));
+    assert!(matches!(
+        &received[1],
+        Request::PostAuthMessageResponse { response: Some(p) } if p == "hunter2"
+    ));
+    assert!(matches!(&received[2], Request::StartSession { cmd, .. } if cmd == &["sway"]));
+}
+
+#[test]
+fn wrong_password_is_auth_failure() {
+    let (result, received) = with_mock(
+        "login-bad-password",
+        vec![
+            secret_prompt(),
+            Response::Error {
+                error_type: ErrorType::AuthError,
+                description: "pam_authenticate: AUTH_ERR".to_string(),
+            },
+        ],
+        || greetd_client::login("alice", "wrong", vec!["sway".to_string()]),
+    );
+
+    assert!(matches!(result, Err(GreetdError::AuthFailed(_))));
+    // The session must not be started after a failed authentication
+    assert_eq!(received.len(), 2);
+}
+
+#[test]
+fn greetd_system_error_is_not_auth_failure() {
+    let (result, _) = with_mock(
+        "login-system-error",
+        vec![
+            secret_prompt(),
+            Response::Error {
+                error_type: ErrorType::Error,
+                description: "pam_open_session failed".to_string(),
+            },
+        ],
+        || greetd_client::login("alice", "hunter2", vec!["sway".to_string()]),
+    );
+
+    assert!(matches!(result, Err(GreetdError::System(_))));
+}
+
+#[test]
+fn stale_session_is_cancelled_and_retried() {
+    let (result, received) = with_mock(
+        "login-stale-session",
+        vec![
+            Response::Error {
+                error_type: ErrorType::Error,
+                description: "a session is already being configured".to_string(),
+            },
+            Response::Success,
+            secret_prompt(),
+            Response::Success,
+        ],
+        || greetd_client::login("alice", "hunter2", vec!["sway".to_string()]),
+    );
+
+    assert!(result.is_ok());
+    assert!(matches!(&received[0], Request::CreateSession { .. }));
+    assert!(matches!(&received[1], Request::CancelSession));
+    assert!(matches!(&received[2], Request::CreateSession { username } if username == "alice"));
+    assert!(matches!(&received[4], Request::StartSession { .. }));
+}
+
+#[test]
+fn only_greetds_in_progress_error_counts_as_stale() {
+    assert!(GreetdError::System(greetd_client::SESSION_IN_PROGRESS.to_string()).is_session_in_progress());
+    assert!(!GreetdError::System("user is already logged in".to_string()).is_session_in_progress());
+    assert!(!GreetdError::AuthFailed(greetd_client::SESSION_IN_PROGRESS.to_string()).is_session_in_progress());
+}
+
+#[test]
+fn unexpected_message_is_protocol_error() {
+    let (result, _) = with_mock("auth-unexpected", vec![Response::Success], || async {
+        let mut client = GreetdClient::connect().await?;
+        client.authenticate("alice", "hunter2").await
+    });
+
+    let err = result.unwrap_err();
+    assert!(matches!(err, GreetdError::Protocol(_)));
+    assert!(err.to_string().contains("greetd-ipc 0.10"));
+}
+
+#[test]
+fn connect_fails_without_daemon() {
+    let _guard = SOCKET_ENV.lock().unwrap_or_else(|e| e.into_inner());
+    std::env::set_var("GREETD_SOCK", socket_path("missing"));
+
+    let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    let result = rt.block_on(GreetdClient::connect());
+    assert!(matches!(result, Err(GreetdError::ConnectionFailed(_))));
+}
+
+#[test]
+fn connects_to_abstract_socket() {
+    use std::os::linux::net::SocketAddrExt;
+
+    let name = format!("hypr-greeter-{}-abstract", std::process::id());
+    let addr = std::os::unix::net::SocketAddr::from_abstract_name(&name).unwrap();
+    let listener = std::os::unix::net::UnixListener::bind_addr(&addr).unwrap();
+    listener.set_nonblocking(true).unwrap();
+
+    let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    let result = rt.block_on(async {
+        let listener = UnixListener::from_std(listener).unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let request = read_request(&mut stream).await;
+            write_response(&mut stream, &Response::Success).await;
+            request
+        });
+        let mut client = GreetdClient::connect_to(&format!("@{}", name)).await?;
+        client.cancel_session().await?;
+        Ok::<_, GreetdError>(server.await.unwrap())
+    });
+
+    assert!(matches!(result, Ok(Some(Request::CancelSession))));
+}
+
+#[cfg(not(feature = "tcp"))]
+#[test]
+fn tcp_target_needs_feature() {
+    let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    let result = rt.block_on(GreetdClient::connect_to("tcp:127.0.0.1:1"));
+    assert!(matches!(result, Err(GreetdError::ConnectionFailed(msg)) if msg.contains("tcp feature")));
+}
+
+#[test]
+fn greetd_authenticator_drives_conversation() {
+    use hypr_greeter::auth::{Authenticator, GreetdAuthenticator};
+
+    let (result, received) = with_mock("authenticator", vec![secret_prompt(), Response::Success], || async {
+        let mut auth: Box<dyn Authenticator> = Box::new(GreetdAuthenticator::from_config(None));
+        auth.create_session("alice").await?;
+        auth.post_response(Some("hunter2".to_string())).await?;
+        auth.start_session(vec!["sway".to_string()], vec!["LANG=C".to_string()]).await?;
+        auth.close();
+        auth.post_response(None).await.map(|_| ())
+    });
+
+    assert!(matches!(result, Err(GreetdError::Protocol(_))));
+    assert!(matches!(&received[2], Request::StartSession { env, .. } if env == &["LANG=C"]));
+}
@@ -0,0 +1,168 @@
+use hypr_greeter::config::{Config, Session, SessionOptions};
+use hypr_greeter::session::{
+    build_argv, build_env, effective_sessions, find_program, parse_desktop_entry, parse_env_file,
+    read_drop_in_sessions, read_env_files, resolve_session, session_list, LAST_SESSION,
+};
+
+#[test]
+fn parses_simple_assignments() {
+    let vars = parse_env_file(
+        "# comment\n\nPATH=/usr/bin\nexport EDITOR=vim\nQUOTED=\"a b\"\nnot an assignment\nBAD-KEY=x\n",
+    );
+    assert_eq!(
+        vars,
+        vec![
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            ("EDITOR".to_string(), "vim".to_string()),
+            ("QUOTED".to_string(), "a b".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn later_env_files_override_earlier_ones() {
+    let dir = std::env::temp_dir().join(format!("hypr-greeter-env-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let system = dir.join("environment");
+    let user = dir.join("10-user.conf");
+    std::fs::write(&system, "LANG=C\nEDITOR=nano\n").unwrap();
+    std::fs::write(&user, "EDITOR=vim\n").unwrap();
+
+    let env = read_env_files(&[system, dir.join("missing.conf"), user]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(env, vec!["LANG=C".to_string(), "EDITOR=vim".to_string()]);
+}
+
+#[test]
+fn parses_session_desktop_entry() {
+    let session = parse_desktop_entry(
+        "[Desktop Entry]\nName=Hyprland\nComment=An dynamic tiling compositor\nExec=Hyprland %U\nType=Application\n\n[Desktop Action new]\nName=Other\n",
+    )
+    .unwrap();
+    assert_eq!(session.name, "Hyprland");
+    assert_eq!(session.command, "Hyprland");
+
+    assert!(parse_desktop_entry("[Desktop Entry]\nName=Hidden\nExec=x\nNoDisplay=true\n").is_none());
+    assert!(parse_desktop_entry("[Desktop Entry]\nName=No command\n").is_none());
+}
+
+#[test]
+fn last_session_entry_resolves_to_last_used_session() {
+    let opts = SessionOptions { last_session_entry: true, ..SessionOptions::default() };
+    let configured = vec![Session::new("Hyprland", "start-hyprland"), Session::new("Sway", "sway")];
+    let (sessions, errors) = effective_sessions(&configured, &opts, &[]);
+    assert!(errors.is_empty());
+
+    assert_eq!(sessions.len(), 3);
+    assert_eq!(sessions[0].command, LAST_SESSION);
+    assert_eq!(resolve_session(&sessions[0], &sessions, Some("Sway")).unwrap().command, "sway");
+    assert_eq!(resolve_session(&sessions[0], &sessions, Some("Gone")).unwrap().command, "start-hyprland");
+    assert_eq!(resolve_session(&sessions[0], &sessions, None).unwrap().command, "start-hyprland");
+}
+
+#[test]
+fn session_env_overrides_global_env() {
+    let mut config = Config::default();
+    config.session.load_environment_files = false;
+    config.session.path = Some("/usr/bin".to_string());
+    config.session_env = vec!["QT_QPA_PLATFORM=wayland".to_string(), "GTK_THEME=Adwaita".to_string()];
+    let mut sway = Session::new("Sway", "sway");
+    sway.env = vec!["GTK_THEME=Adwaita:dark".to_string(), "XDG_CURRENT_DESKTOP=sway".to_string()];
+
+    assert_eq!(
+        build_env(&config, Some(&sway), None),
+        vec!["PATH=/usr/bin", "QT_QPA_PLATFORM=wayland", "GTK_THEME=Adwaita:dark", "XDG_CURRENT_DESKTOP=sway"]
+    );
+}
+
+#[test]
+fn session_path_is_forwarded_and_overridable() {
+    let mut config = Config::default();
+    config.session.load_environment_files = false;
+    let greeter_path = std::env::var("PATH").unwrap();
+    assert_eq!(build_env(&config, None, None), vec![format!("PATH={}", greeter_path)]);
+
+    config.session.path = Some(String::new());
+    assert!(build_env(&config, None, None).is_empty());
+
+    config.session.path = Some("/usr/bin".to_string());
+    let mut sway = Session::new("Sway", "sway");
+    sway.env = vec!["PATH=/opt/sway/bin:/usr/bin".to_string()];
+    assert_eq!(build_env(&config, Some(&sway), None), vec!["PATH=/opt/sway/bin:/usr/bin"]);
+}
+
+#[test]
+fn finds_programs_on_path() {
+    assert_eq!(find_program("sh", "/nonexistent:/bin"), Some("/bin/sh".into()));
+    assert_eq!(find_program("/bin/sh", ""), Some("/bin/sh".into()));
+    assert_eq!(find_program("sh", "/nonexistent"), None);
+    assert_eq!(find_program("/etc/hostname-not-there", "/bin"), None);
+}
+
+#[test]
+fn cwd_wraps_the_session_command() {
+    let opts = Config::default().session;
+    let mut exec = Session::new("Sway", "sway --unsupported-gpu");
+    exec.cwd = Some("/srv/work".to_string());
+    assert_eq!(
+        build_argv(&exec, &opts).unwrap(),
+        vec!["/bin/sh", "-c", "cd \"$0\" && exec \"$@\"", "/srv/work", "sway", "--unsupported-gpu"]
+    );
+
+    let mut shell = Session::new("Shell", "exec ./start.sh");
+    shell.shell = true;
+    shell.cwd = Some("/home/o'brien".to_string());
+    assert_eq!(
+        build_argv(&shell, &opts).unwrap(),
+        vec!["/bin/sh", "-lc", "cd '/home/o'\\''brien' && exec ./start.sh"]
+    );
+}
+
+#[test]
+fn session_list_prints_one_line_per_session() {
+    let mut config = Config::default();
+    let mut shell = Session::new("Shell", "exec bash");
+    shell.shell = true;
+    config.sessions = vec![Session::new("Sway", "sway"), shell];
+    config.session.last_session_entry = true;
+
+    assert_eq!(
+        session_list(&config),
+        "Default / Last\t@last\tlast\nSway\tsway\texec\nShell\texec bash\tshell\n"
+    );
+}
+
+#[test]
+fn reads_drop_in_session_files() {
+    let dir = std::env::temp_dir().join(format!("hypr-greeter-sessions-d-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("20-niri.toml"), "name = \"niri\"\ncommand = \"niri-session\"\n").unwrap();
+    std::fs::write(dir.join("10-river.json"), r#"{"name": "River", "command": "river", "shell": true}"#).unwrap();
+    std::fs::write(dir.join("30-broken.toml"), "name = \"no command\"\n").unwrap();
+    std::fs::write(dir.join("README"), "not a session").unwrap();
+
+    let (sessions, errors) = read_drop_in_sessions(std::slice::from_ref(&dir));
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("30-broken.toml"));
+    let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["River", "niri"]);
+    assert!(sessions[0].shell);
+    assert_eq!(sessions[1].command, "niri-session");
+}
+
+#[test]
+fn single_quotes_and_escapes_stop_expansion() {
+    std::env::set_var("HYPR_GREETER_TEST_WORD", "a b");
+    let opts = Config::default().session;
+    let cmd = Session::new("Echo", r#"echo '$HYPR_GREETER_TEST_WORD' \$HYPR_GREETER_TEST_WORD "\${HYPR_GREETER_TEST_WORD}""#);
+    assert_eq!(
+        build_argv(&cmd, &opts).unwrap(),
+        vec!["echo", "$HYPR_GREETER_TEST_WORD", "$HYPR_GREETER_TEST_WORD", "${HYPR_GREETER_TEST_WORD}"]
+    );
+
+    // Unquoted and double-quoted references expand, and stay one word
+    let cmd = Session::new("Echo", r#"echo $HYPR_GREETER_TEST_WORD "x${HYPR_GREETER_TEST_WORD}y""#);
+    assert_eq!(build_argv(&cmd, &opts).unwrap(), vec!["echo", "a b", "xa by"]);
+}
@@ -0,0 +1,16 @@
+use hypr_greeter::sysinfo::{parse_meminfo, parse_os_release};
+
+#[test]
+fn distro_prefers_pretty_name() {
+    let os_release = "NAME=\"Arch Linux\"\nPRETTY_NAME=\"Arch Linux (rolling)\"\nID=arch\n";
+    assert_eq!(parse_os_release(os_release).as_deref(), Some("Arch Linux (rolling)"));
+    assert_eq!(parse_os_release("NAME=Debian\n").as_deref(), Some("Debian"));
+    assert_eq!(parse_os_release("ID=void\n"), None);
+}
+
+#[test]
+fn memory_is_used_over_total() {
+    let meminfo = "MemTotal:       16777216 kB\nMemFree:         1048576 kB\nMemAvailable:   12582912 kB\n";
+    assert_eq!(parse_meminfo(meminfo).as_deref(), Some("4.0 / 16.0 GiB"));
+    assert_eq!(parse_meminfo("MemTotal: 1024 kB\n"), None);
+}
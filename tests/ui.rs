@@ -0,0 +1,881 @@
+use hypr_greeter::config::{Config, Session, WidgetConfig};
+use hypr_greeter::ui::{self, App, Focus, MessageLevel};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn test_config() -> Config {
+    let mut config = Config::default();
+    config.ui.title = "Test Greeter".to_string();
+    config.ui.show_clock = false;
+    config.ui.show_date = false;
+    config.sessions = vec![
+        Session::new("Alpha", "alpha"),
+        Session::new("Beta", "beta"),
+    ];
+    config
+}
+
+fn render(app: &App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(80, 40)).unwrap();
+    terminal.draw(|f| ui::draw(f, app)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        for x in 0..buffer.area.width {
+            out.push_str(buffer.get(x, y).symbol());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[test]
+fn renders_title_masked_password_and_session() {
+    let mut app = App::new(test_config());
+    app.username = "alice".to_string();
+    app.password = "secret".to_string();
+
+    let screen = render(&app);
+    assert!(screen.contains("Test Greeter"));
+    assert!(screen.contains("alice"));
+    assert!(screen.contains("******"));
+    assert!(!screen.contains("*******"));
+    assert!(!screen.contains("secret"));
+    assert!(screen.contains("Alpha"));
+}
+
+#[test]
+fn renders_plain_password_when_unmasked() {
+    let mut config = test_config();
+    config.security.mask_password = false;
+    let mut app = App::new(config);
+    app.password = "secret".to_string();
+
+    assert!(render(&app).contains("secret"));
+}
+
+#[test]
+fn focused_session_shows_arrows() {
+    let mut app = App::new(test_config());
+    app.focus = Focus::Session;
+    app.next_session();
+
+    assert!(render(&app).contains("< Beta >"));
+}
+
+#[test]
+fn focus_cycles_in_both_directions() {
+    let mut app = App::new(test_config());
+    assert_eq!(app.focus, Focus::Username);
+
+    app.next_focus();
+    assert_eq!(app.focus, Focus::Password);
+    app.next_focus();
+    assert_eq!(app.focus, Focus::Session);
+    app.next_focus();
+    assert_eq!(app.focus, Focus::Username);
+
+    app.prev_focus();
+    assert_eq!(app.focus, Focus::Session);
+    app.prev_focus();
+    assert_eq!(app.focus, Focus::Password);
+}
+
+#[test]
+fn session_selection_stays_in_bounds() {
+    let mut app = App::new(test_config());
+
+    app.prev_session();
+    assert_eq!(app.selected_session, 0);
+
+    app.next_session();
+    app.next_session();
+    app.next_session();
+    assert_eq!(app.selected_session, 1);
+    assert_eq!(app.current_session().unwrap().name, "Beta");
+}
+
+#[test]
+fn select_session_ignores_out_of_range() {
+    let mut app = App::new(test_config());
+
+    assert!(app.select_session(1));
+    assert_eq!(app.selected_session, 1);
+    assert!(!app.select_session(5));
+    assert_eq!(app.selected_session, 1);
+}
+
+#[test]
+fn attempt_counter_shows_after_failure() {
+    let mut app = App::new(test_config());
+    assert!(!render(&app).contains("Attempt"));
+
+    app.record_failed_attempt();
+    assert!(render(&app).contains("Attempt 2"));
+
+    app.reset_attempts();
+    assert!(!render(&app).contains("Attempt"));
+}
+
+#[test]
+fn renders_last_login_when_recorded() {
+    let mut config = test_config();
+    config.last_user = Some("bob".to_string());
+    config.last_login = chrono::DateTime::parse_from_rfc3339("2024-05-01T08:30:00+00:00")
+        .ok()
+        .map(|t| t.with_timezone(&chrono::Local));
+    let app = App::new(config);
+
+    assert!(render(&app).contains("Last login:"));
+    assert!(render(&app).contains("as bob"));
+}
+
+#[test]
+fn debug_overlay_shows_parsed_argv() {
+    let mut config = test_config();
+    config.sessions[0].command = "swaymsg exec \"foo bar\"".to_string();
+    let mut app = App::new(config);
+    app.show_debug = true;
+
+    let screen = render(&app);
+    assert!(screen.contains("Launch preview"));
+    assert!(screen.contains("[2] \"foo bar\""));
+    assert!(screen.contains("greetd-ipc 0.10"));
+}
+
+#[test]
+fn password_title_uses_greetd_prompt() {
+    let mut app = App::new(test_config());
+    assert_eq!(app.password_title(), "Password");
+
+    app.set_auth_prompt("Verification code: ".to_string(), false);
+    assert_eq!(app.password_title(), "Verification code");
+    assert!(render(&app).contains("Verification code"));
+}
+
+#[test]
+fn rounded_border_style_is_applied() {
+    let mut config = test_config();
+    config.ui.border_style = "rounded".to_string();
+    let app = App::new(config);
+
+    assert!(render(&app).contains('\u{256d}'));
+}
+
+#[test]
+fn autofill_focus_modes() {
+    let mut config = test_config();
+    config.default_user = Some("alice".to_string());
+    assert_eq!(App::new(config.clone()).focus, Focus::Password);
+
+    config.ui.autofill_focus = "username".to_string();
+    let app = App::new(config);
+    assert_eq!(app.focus, Focus::Username);
+    assert_eq!(app.username, "alice");
+}
+
+#[test]
+fn hidden_session_selector_is_skipped() {
+    let mut config = test_config();
+    config.ui.show_session = false;
+    let mut app = App::new(config);
+
+    assert!(!render(&app).contains("Alpha"));
+    app.next_focus();
+    app.next_focus();
+    assert_eq!(app.focus, Focus::Username);
+    app.prev_focus();
+    assert_eq!(app.focus, Focus::Password);
+    assert!(!app.select_session(1));
+    assert_eq!(app.current_session().unwrap().name, "Alpha");
+}
+
+#[test]
+fn username_policy_rejects_typed_characters() {
+    let mut config = test_config();
+    config.security.max_username_len = 4;
+    config.security.username_charset = Some("a-z0-9_-".to_string());
+    let mut app = App::new(config);
+
+    for c in "ab-C".chars() {
+        app.push_username_char(c);
+    }
+    assert_eq!(app.username, "ab-");
+    assert!(app.error_message.as_deref().unwrap().contains("'C'"));
+
+    assert!(app.push_username_char('1'));
+    assert!(!app.push_username_char('2'));
+    assert_eq!(app.username, "ab-1");
+}
+
+#[test]
+fn autofilled_username_is_hinted_until_edited() {
+    let mut config = test_config();
+    config.default_user = Some("alice".to_string());
+    let mut app = App::new(config);
+
+    assert!(render(&app).contains("logging in as alice"));
+    app.push_username_char('x');
+    assert!(!render(&app).contains("logging in as"));
+}
+
+#[test]
+fn session_command_preview_is_optional() {
+    let mut config = test_config();
+    config.sessions[0].command = "start-alpha --wayland".to_string();
+    assert!(!render(&App::new(config.clone())).contains("start-alpha"));
+
+    config.ui.show_session_command = true;
+    assert!(render(&App::new(config)).contains("start-alpha --wayland"));
+}
+
+#[test]
+fn failure_hook_is_rate_limited() {
+    let mut app = App::new(test_config());
+    assert_eq!(app.failure_hook_due(), None);
+
+    app.config.hooks.on_failure = Some("logger failed".to_string());
+    assert_eq!(app.failure_hook_due().as_deref(), Some("logger failed"));
+    assert_eq!(app.failure_hook_due(), None);
+
+    app.config.hooks.on_failure_interval_secs = 0;
+    assert!(app.failure_hook_due().is_some());
+}
+
+#[test]
+fn errors_show_icon_and_expire() {
+    let mut config = test_config();
+    config.ui.error_timeout_secs = 5;
+    let mut app = App::new(config);
+
+    app.set_error("Login failed".to_string());
+    assert!(render(&app).contains("\u{26a0} Login failed"));
+    app.on_tick();
+    assert!(app.error_message.is_some());
+
+    app.error_deadline = Some(std::time::Instant::now());
+    app.on_tick();
+    assert!(app.error_message.is_none());
+}
+
+#[test]
+fn config_error_banner_is_shown() {
+    let mut config = test_config();
+    config.load_error = Some("expected `=`".to_string());
+
+    assert!(render(&App::new(config)).contains("Using default config (config error: expected `=`)"));
+}
+
+#[test]
+fn extra_secret_field_joins_focus_cycle() {
+    let mut config = test_config();
+    config.ui.extra_secret = Some("Keyring passphrase".to_string());
+    let mut app = App::new(config);
+    app.extra_secret = "open sesame".to_string();
+
+    let screen = render(&app);
+    assert!(screen.contains("Keyring passphrase"));
+    assert!(screen.contains("***********"));
+    assert!(!screen.contains("open sesame"));
+
+    app.next_focus();
+    app.next_focus();
+    assert_eq!(app.focus, Focus::ExtraSecret);
+    app.next_focus();
+    assert_eq!(app.focus, Focus::Session);
+}
+
+#[test]
+fn help_bar_can_move_or_hide() {
+    let mut config = test_config();
+    let bottom = render(&App::new(config.clone()));
+    assert!(bottom.lines().last().unwrap().contains("Tab: Next Field"));
+
+    config.ui.help_position = "top".to_string();
+    let top = render(&App::new(config.clone()));
+    assert!(top.lines().next().unwrap().contains("Tab: Next Field"));
+
+    config.ui.show_help = false;
+    assert!(!render(&App::new(config)).contains("Tab: Next Field"));
+}
+
+#[test]
+fn readline_kills_edit_focused_field() {
+    let mut app = App::new(test_config());
+    app.username = "jane doe  ".to_string();
+    app.kill_word();
+    assert_eq!(app.username, "jane ");
+    app.kill_word();
+    assert_eq!(app.username, "");
+
+    app.focus = Focus::Password;
+    app.password = "hunter2".to_string();
+    app.kill_to_start();
+    assert!(app.password.is_empty());
+}
+
+#[test]
+fn confirm_session_needs_second_enter() {
+    let mut config = test_config();
+    config.sessions[1].confirm = true;
+    let mut app = App::new(config);
+
+    assert!(app.confirm_session());
+    app.next_session();
+    assert!(!app.confirm_session());
+    assert!(render(&app).contains("Press Enter again to log in to Beta"));
+    assert!(app.confirm_session());
+
+    assert!(!app.confirm_session());
+    app.cancel_session_confirm();
+    assert!(!app.confirm_session());
+}
+
+#[test]
+fn idle_ticks_do_not_dirty_the_frame() {
+    let mut config = test_config();
+    config.ui.animations = true;
+    let mut app = App::new(config);
+    app.dirty = false;
+
+    app.on_tick();
+    assert!(!app.dirty);
+
+    app.next_focus();
+    app.on_tick();
+    assert!(app.dirty);
+}
+
+#[test]
+fn compact_layout_uses_label_rows() {
+    let mut config = test_config();
+    config.ui.layout_style = "compact".to_string();
+    let mut app = App::new(config);
+    app.username = "alice".to_string();
+    app.password = "pw".to_string();
+
+    let screen = render(&app);
+    assert!(screen.contains("Username: alice"));
+    assert!(screen.contains("Password: **"));
+    assert!(screen.contains("Session: Alpha"));
+    assert!(!screen.contains('\u{250c}'));
+}
+
+#[test]
+fn welcome_message_replaces_errors() {
+    let mut app = App::new(test_config());
+    app.set_error("Login failed".to_string());
+    app.success_message = Some("Welcome, alice".to_string());
+
+    let screen = render(&app);
+    assert!(screen.contains("Welcome, alice"));
+    assert!(!screen.contains("Login failed"));
+}
+
+#[test]
+fn login_name_is_trimmed_by_default() {
+    let mut app = App::new(test_config());
+    app.username = " alice \t".to_string();
+    assert_eq!(app.login_name(), "alice");
+
+    app.config.security.trim_username = false;
+    assert_eq!(app.login_name(), " alice \t");
+}
+
+#[test]
+fn empty_password_placeholder_follows_policy() {
+    let mut config = test_config();
+    assert!(!render(&App::new(config.clone())).contains("no password required"));
+
+    config.security.allow_empty_password = true;
+    let mut app = App::new(config);
+    assert!(render(&app).contains("(no password required)"));
+    app.password.push('x');
+    assert!(!render(&app).contains("no password required"));
+}
+
+#[test]
+fn hostname_is_shown_under_title() {
+    let mut app = App::new(test_config());
+    app.hostname = Some("lab-pc-07".to_string());
+
+    assert!(render(&app).contains("lab-pc-07"));
+}
+
+#[test]
+fn focus_dim_fades_title_on_password() {
+    let mut config = test_config();
+    config.ui.focus_dim = true;
+    let mut app = App::new(config);
+
+    let title_modifier = |app: &App| {
+        let mut terminal = Terminal::new(TestBackend::new(80, 40)).unwrap();
+        terminal.draw(|f| ui::draw(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .flat_map(|y| (0..buffer.area.width).map(move |x| (x, y)))
+            .map(|(x, y)| buffer.get(x, y))
+            .find(|cell| cell.symbol() == "T")
+            .unwrap()
+            .modifier
+    };
+
+    assert!(!title_modifier(&app).contains(ratatui::style::Modifier::DIM));
+    app.focus = Focus::Password;
+    assert!(title_modifier(&app).contains(ratatui::style::Modifier::DIM));
+}
+
+#[test]
+fn quitting_maps_to_exit_codes() {
+    use hypr_greeter::{ExitCode, Outcome};
+
+    let mut app = App::new(test_config());
+    assert_eq!(ExitCode::from_outcome(&app.quit_outcome(), false).code(), 2);
+    assert_eq!(ExitCode::from_outcome(&app.quit_outcome(), true).code(), 3);
+
+    app.greetd_unreachable = true;
+    assert_eq!(app.quit_outcome(), Outcome::GreetdUnreachable);
+    assert_eq!(ExitCode::from_outcome(&app.quit_outcome(), true).code(), 4);
+
+    let logged_in = Outcome::LoggedIn { username: "alice".to_string() };
+    assert_eq!(ExitCode::from_outcome(&logged_in, false), ExitCode::SessionStarted);
+    assert_eq!(ExitCode::from_outcome(&Outcome::LoginFailed, true).code(), 5);
+}
+
+#[test]
+fn finds_session_by_name_then_command() {
+    let sessions = test_config().sessions;
+    assert_eq!(ui::find_session(&sessions, "Beta"), Some(1));
+    assert_eq!(ui::find_session(&sessions, "alpha"), Some(0));
+    assert_eq!(ui::find_session(&sessions, "gamma"), None);
+}
+
+#[test]
+fn locked_default_user_cannot_be_changed() {
+    let mut config = test_config();
+    config.default_user = Some("kiosk".to_string());
+    config.disable_autofill = Some(true);
+    config.security.lock_default_user = true;
+    let mut app = App::new(config);
+
+    assert_eq!(app.username, "kiosk");
+    assert_eq!(app.focus, Focus::Password);
+    assert!(!app.focus_order().contains(&Focus::Username));
+    assert!(!app.push_username_char('x'));
+    assert_eq!(app.username, "kiosk");
+}
+
+#[test]
+fn selected_password_is_replaced_by_next_keystroke() {
+    let mut config = test_config();
+    config.security.on_error_password = Some("select".to_string());
+    let mut app = App::new(config);
+    app.password = "hunter3".to_string();
+
+    app.set_error("Authentication failed".to_string());
+    assert_eq!(app.password, "hunter3");
+    assert!(app.password_selected);
+
+    app.push_password_char('x');
+    assert_eq!(app.password, "x");
+    assert!(!app.password_selected);
+}
+
+#[test]
+fn password_on_error_follows_legacy_flag() {
+    let mut config = test_config();
+    config.security.clear_password_on_error = false;
+    assert_eq!(config.security.password_on_error(), "keep");
+
+    let mut app = App::new(config);
+    app.password = "hunter3".to_string();
+    app.set_error("Authentication failed".to_string());
+    assert_eq!(app.password, "hunter3");
+    assert!(!app.password_selected);
+}
+
+#[test]
+fn field_order_controls_tab_cycle_and_layout() {
+    let mut config = test_config();
+    config.ui.field_order = ["session", "username", "password"].map(String::from).to_vec();
+    let app = App::new(config);
+
+    assert_eq!(app.focus_order(), vec![Focus::Session, Focus::Username, Focus::Password]);
+    let screen = render(&app);
+    let session = screen.find("Session").unwrap();
+    let username = screen.find("Username").unwrap();
+    assert!(session < username);
+}
+
+#[test]
+fn password_title_counts_typed_characters() {
+    let mut config = test_config();
+    config.ui.show_password_length = true;
+    let mut app = App::new(config);
+    app.password = "hunter2".to_string();
+
+    assert!(render(&app).contains("Password (7)"));
+}
+
+#[test]
+fn pam_messages_accumulate_and_scroll() {
+    let mut app = App::new(test_config());
+    app.push_pam_message(MessageLevel::Info, "Last failed login: yesterday".to_string());
+    app.push_pam_message(MessageLevel::Error, "Password expires in 3 days".to_string());
+
+    let screen = render(&app);
+    assert!(screen.contains("Last failed login: yesterday"));
+    assert!(screen.contains("Password expires in 3 days"));
+
+    app.scroll_pam_messages(true);
+    assert_eq!(app.pam_scroll, 1);
+    assert!(!render(&app).contains("Password expires in 3 days"));
+
+    app.clear_pam_messages();
+    assert!(app.pam_messages.is_empty());
+}
+
+#[test]
+fn blank_screen_after_failures_needs_unlock_sequence() {
+    let mut config = test_config();
+    config.security.blank_after_failures = 2;
+    config.security.blank_unlock_sequence = Some("open42".to_string());
+    let mut app = App::new(config);
+
+    app.record_failed_attempt();
+    assert!(!app.blank_locked);
+    app.record_failed_attempt();
+    assert!(app.blank_locked);
+    assert!(!render(&app).contains("Password"));
+
+    "xxopen4".chars().for_each(|c| app.feed_unlock(c));
+    assert!(app.blank_locked);
+    app.feed_unlock('2');
+    assert!(!app.blank_locked);
+    assert_eq!(app.failed_attempts, 0);
+}
+
+#[test]
+fn tab_can_skip_the_session_selector() {
+    let mut config = test_config();
+    config.ui.tab_skips_session = true;
+    let mut app = App::new(config);
+    app.set_focus(Focus::Password);
+
+    app.next_focus();
+    assert_eq!(app.focus, Focus::Username);
+    app.prev_focus();
+    assert_eq!(app.focus, Focus::Password);
+}
+
+#[test]
+fn focus_stops_at_the_ends_without_wrap() {
+    let mut config = test_config();
+    config.ui.focus_wrap = false;
+    let mut app = App::new(config);
+    app.set_focus(Focus::Session);
+
+    app.next_focus();
+    assert_eq!(app.focus, Focus::Session);
+    app.set_focus(Focus::Username);
+    app.prev_focus();
+    assert_eq!(app.focus, Focus::Username);
+    app.next_focus();
+    assert_eq!(app.focus, Focus::Password);
+}
+
+#[test]
+fn idle_timeout_returns_the_configured_action_once() {
+    let mut config = test_config();
+    config.security.input_timeout = 30;
+    config.security.idle_action = "screensaver".to_string();
+    let mut app = App::new(config);
+
+    assert_eq!(app.idle_action_due(), None);
+    app.last_input -= std::time::Duration::from_secs(31);
+    assert_eq!(app.idle_action_due().as_deref(), Some("screensaver"));
+    assert_eq!(app.idle_action_due(), None);
+
+    app.password = "hunter2".to_string();
+    app.clear_input();
+    assert!(app.password.is_empty());
+}
+
+#[test]
+fn start_over_clears_fields_and_messages() {
+    let mut app = App::new(test_config());
+    app.username = "alcie".to_string();
+    app.password = "hunter2".to_string();
+    app.set_focus(Focus::Session);
+    app.show_error("Login failed".to_string());
+
+    app.start_over();
+    assert!(app.username.is_empty());
+    assert!(app.password.is_empty());
+    assert!(app.error_message.is_none());
+    assert_eq!(app.focus, Focus::Username);
+}
+
+#[test]
+fn small_terminal_shows_required_size() {
+    let app = App::new(test_config());
+    let mut terminal = Terminal::new(TestBackend::new(30, 10)).unwrap();
+    terminal.draw(|f| ui::draw(f, &app)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+
+    assert!(screen.contains("Terminal too small"));
+    assert!(!screen.contains("Username"));
+}
+
+#[test]
+fn reveals_only_the_last_typed_character() {
+    let mut config = test_config();
+    config.security.reveal_last_char = true;
+    let mut app = App::new(config);
+    "hunter".chars().for_each(|c| app.push_password_char(c));
+
+    assert!(render(&app).contains("*****r"));
+
+    app.password_typed_at = app.password_typed_at.map(|at| at - std::time::Duration::from_secs(2));
+    app.on_tick();
+    assert!(render(&app).contains("******"));
+    assert!(!render(&app).contains("*****r"));
+}
+
+#[test]
+fn corner_widgets_show_text_and_builtins() {
+    let mut config = test_config();
+    config.ui.widgets = vec![
+        WidgetConfig { corner: "tl".to_string(), text: Some("Kiosk 7".to_string()), command: None, builtin: None, refresh_secs: 30 },
+        WidgetConfig { corner: "br".to_string(), text: None, command: None, builtin: Some("uptime".to_string()), refresh_secs: 30 },
+    ];
+    let app = App::new(config);
+    let screen = render(&app);
+
+    assert!(screen.starts_with("Kiosk 7"));
+    assert!(screen.contains("up "));
+}
+
+#[test]
+fn quantizes_colors_for_limited_terminals() {
+    use ratatui::style::Color;
+    use hypr_greeter::ui::{quantize, ColorDepth};
+
+    assert_eq!(quantize(Color::Rgb(0xf7, 0x76, 0x8e), ColorDepth::TrueColor), Color::Rgb(0xf7, 0x76, 0x8e));
+    assert_eq!(quantize(Color::Rgb(250, 5, 5), ColorDepth::Ansi16), Color::LightRed);
+    assert_eq!(quantize(Color::Rgb(0, 0, 0), ColorDepth::Ansi256), Color::Indexed(16));
+    assert_eq!(quantize(Color::Rgb(128, 128, 128), ColorDepth::Ansi256), Color::Indexed(244));
+    assert_eq!(quantize(Color::Yellow, ColorDepth::Ansi16), Color::Yellow);
+
+    let mut config = test_config();
+    config.ui.color_mode = "16".to_string();
+    let app = App::new(config);
+    let mut terminal = Terminal::new(TestBackend::new(80, 40)).unwrap();
+    terminal.draw(|f| ui::draw(f, &app)).unwrap();
+    let cells = terminal.backend().buffer().content();
+    assert!(cells.iter().all(|c| !matches!(c.fg, Color::Rgb(..)) && !matches!(c.bg, Color::Rgb(..))));
+}
+
+#[test]
+fn prompt_form_fields_follow_the_password() {
+    let mut config = test_config();
+    config.security.prompt_form = vec!["Verification code".to_string(), "PIN".to_string()];
+    let mut app = App::new(config);
+
+    assert_eq!(
+        app.focus_order(),
+        vec![Focus::Username, Focus::Password, Focus::Prompt(0), Focus::Prompt(1), Focus::Session]
+    );
+    app.set_focus(Focus::Password);
+    app.next_focus();
+    assert_eq!(app.focus, Focus::Prompt(0));
+
+    app.prompt_answers[0] = "123456".to_string();
+    let screen = render(&app);
+    assert!(screen.contains("Verification code"));
+    assert!(screen.contains("PIN"));
+    assert!(screen.contains("******"));
+    assert!(!screen.contains("123456"));
+
+    app.clear_input();
+    assert!(app.prompt_answers.iter().all(String::is_empty));
+}
+
+#[test]
+fn clock_and_date_use_their_theme_colors() {
+    use ratatui::style::Color;
+
+    let mut config = test_config();
+    config.ui.show_clock = true;
+    config.ui.show_date = true;
+    config.ui.color_mode = "truecolor".to_string();
+    config.ui.colors.clock = Some("#123456".to_string());
+    config.ui.colors.date = Some("#654321".to_string());
+    let app = App::new(config);
+    let mut terminal = Terminal::new(TestBackend::new(80, 40)).unwrap();
+    terminal.draw(|f| ui::draw(f, &app)).unwrap();
+    let cells = terminal.backend().buffer().content();
+
+    assert!(cells.iter().any(|c| c.fg == Color::Rgb(0x12, 0x34, 0x56) && c.symbol() != " "));
+    assert!(cells.iter().any(|c| c.fg == Color::Rgb(0x65, 0x43, 0x21) && c.symbol() != " "));
+}
+
+#[test]
+fn slow_login_progress_escalates() {
+    use std::time::{Duration, Instant};
+
+    let mut app = App::new(test_config());
+    assert_eq!(ui::auth_progress(&app), None);
+
+    app.auth_started = Some(Instant::now());
+    assert!(ui::auth_progress(&app).unwrap().ends_with("Authenticating\u{2026}"));
+    assert!(render(&app).contains("Authenticating"));
+
+    app.auth_started = Some(Instant::now() - Duration::from_secs(4));
+    assert!(ui::auth_progress(&app).unwrap().contains("contacting auth server"));
+}
+
+#[test]
+fn initial_focus_overrides_autofill() {
+    let mut config = test_config();
+    config.ui.initial_focus = Some("session".to_string());
+    assert_eq!(App::new(config.clone()).focus, Focus::Session);
+
+    config.default_user = Some("kiosk".to_string());
+    config.security.lock_default_user = true;
+    config.ui.initial_focus = Some("username".to_string());
+    assert_eq!(App::new(config.clone()).focus, Focus::Password);
+
+    config.ui.show_session = false;
+    config.ui.initial_focus = Some("session".to_string());
+    assert!(config.ui.check_initial_focus().is_err());
+}
+
+#[test]
+fn no_sessions_shows_a_message_and_optional_fallback() {
+    let mut config = test_config();
+    config.sessions.clear();
+    let app = App::new(config.clone());
+    assert!(app.current_session().is_none());
+    assert_eq!(app.status_message.as_deref(), Some(ui::NO_SESSIONS));
+    assert!(render(&app).contains("No sessions available"));
+
+    config.security.allow_fallback_shell = true;
+    let app = App::new(config);
+    let session = app.current_session().unwrap();
+    assert!(session.shell);
+    assert_eq!(session.name, "Shell (fallback)");
+    assert_eq!(app.status_message.as_deref(), Some(ui::NO_SESSIONS));
+}
+
+#[test]
+fn top_offset_pct_places_the_first_field() {
+    let mut config = test_config();
+    config.ui.show_help = false;
+    config.ui.top_offset_pct = Some(50);
+    let screen = render(&App::new(config));
+    let row = screen.lines().position(|l| l.contains("Username")).unwrap();
+    assert_eq!(row, 20);
+}
+
+#[test]
+fn banner_file_renders_next_to_the_fields() {
+    let path = std::env::temp_dir().join(format!("hypr-greeter-ui-banner-{}", std::process::id()));
+    std::fs::write(&path, "Authorized use only").unwrap();
+    let mut config = test_config();
+    config.ui.banner_file = Some(path.clone());
+    let screen = render(&App::new(config.clone()));
+    let banner = screen.lines().position(|l| l.contains("Authorized use only")).unwrap();
+    let username = screen.lines().position(|l| l.contains("Username")).unwrap();
+    assert!(banner < username);
+
+    config.ui.banner_position = "below".to_string();
+    let screen = render(&App::new(config));
+    let banner = screen.lines().position(|l| l.contains("Authorized use only")).unwrap();
+    let session = screen.lines().position(|l| l.contains("Alpha")).unwrap();
+    assert!(banner > session);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn help_bar_shows_the_esc_action() {
+    let help = |esc_action: &str| {
+        let mut config = test_config();
+        config.ui.esc_action = esc_action.to_string();
+        let app = App::new(config);
+        let mut terminal = Terminal::new(TestBackend::new(200, 40)).unwrap();
+        terminal.draw(|f| ui::draw(f, &app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.width).map(|x| buffer.get(x, buffer.area.height - 1).symbol()).collect::<String>()
+    };
+    assert!(help("clear_field").contains("Esc: Clear Field"));
+    assert!(help("clear_all").contains("Esc: Clear All"));
+    assert!(!help("close_overlay").contains("Esc:"));
+}
+
+#[test]
+fn session_env_preselects_in_the_offered_list() {
+    let mut config = test_config();
+    config.session.last_session_entry = true;
+    config.sessions.push(Session::new("Gamma", "gamma"));
+    // Only this test lists "Gamma", so parallel tests keep their default
+    std::env::set_var(hypr_greeter::config::SESSION_ENV, "Gamma");
+    let app = App::new(config);
+    std::env::remove_var(hypr_greeter::config::SESSION_ENV);
+    assert_eq!(app.config.sessions[0].name, "Default / Last");
+    assert_eq!(app.current_session().unwrap().name, "Gamma");
+}
+
+#[test]
+fn clock_uses_the_configured_timezone() {
+    use chrono::TimeZone;
+    let now = chrono::Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+    let mut config = test_config();
+    config.ui.timezone = Some("Asia/Tokyo".to_string());
+    assert_eq!(ui::format_time(now, &config.ui, "%H:%M"), "21:00");
+    assert!(ui::check_timezone(&config.ui).is_ok());
+
+    // An unknown zone is reported and the clock stays on local time
+    config.ui.timezone = Some("Mars/Olympus_Mons".to_string());
+    assert!(ui::check_timezone(&config.ui).is_err());
+    let local = now.with_timezone(&chrono::Local).format("%H:%M").to_string();
+    assert_eq!(ui::format_time(now, &config.ui, "%H:%M"), local);
+}
+
+#[test]
+fn broken_drop_in_is_reported_on_screen() {
+    let dir = std::env::temp_dir().join(format!("hypr-greeter-ui-sessions-d-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("bad.toml"), "name = \"no command\"\n").unwrap();
+    let mut config = test_config();
+    config.session.autodiscover = true;
+    let mut app = App::new(config);
+    app.drop_in_dirs = vec![dir.clone()];
+    app.refresh_sessions();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(render(&app).contains("Ignoring"));
+    assert!(render(&app).contains("bad.toml"));
+}
+
+#[test]
+fn help_bar_lists_only_active_keys() {
+    let help = |config: Config| {
+        let app = App::new(config);
+        let mut terminal = Terminal::new(TestBackend::new(200, 40)).unwrap();
+        terminal.draw(|f| ui::draw(f, &app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.width).map(|x| buffer.get(x, buffer.area.height - 1).symbol()).collect::<String>()
+    };
+    let full = help(test_config());
+    assert!(full.contains("Change Session") && full.contains("F1: Power Off"));
+
+    let mut config = test_config();
+    config.sessions.truncate(1);
+    config.power.poweroff_command = String::new();
+    let short = help(config);
+    assert!(!short.contains("Change Session") && !short.contains("Alt+1-9"));
+    assert!(!short.contains("F1: Power Off") && short.contains("F2: Reboot"));
+
+    let mut config = test_config();
+    config.ui.show_session = false;
+    assert!(!help(config).contains("Change Session"));
+}
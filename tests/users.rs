@@ -0,0 +1,31 @@
+use hypr_greeter::config::UiConfig;
+use hypr_greeter::users::filter_login_users;
+
+const PASSWD: &str = "\
+root:x:0:0:root:/root:/bin/bash
+nobody:x:65534:65534:Nobody:/:/usr/bin/nologin
+alice:x:1000:1000:Alice:/home/alice:/bin/bash
+bob:x:1001:1001:Bob:/home/bob:/usr/bin/zsh
+svc:x:1002:1002::/srv:/usr/bin/nologin
+carol:x:1003:1003::/home/carol:/bin/fish
+";
+
+#[test]
+fn keeps_normal_uids_with_login_shells() {
+    let users = filter_login_users(PASSWD, None, &UiConfig::default());
+    assert_eq!(users, ["alice", "bob", "carol"]);
+}
+
+#[test]
+fn respects_etc_shells_and_lists() {
+    let shells = "# valid shells\n/bin/bash\n/usr/bin/zsh\n";
+    let mut ui = UiConfig::default();
+    assert_eq!(filter_login_users(PASSWD, Some(shells), &ui), ["alice", "bob"]);
+
+    ui.user_list_exclude = vec!["alice".to_string()];
+    assert_eq!(filter_login_users(PASSWD, Some(shells), &ui), ["bob"]);
+
+    ui.user_list_exclude.clear();
+    ui.user_list_include = vec!["carol".to_string(), "alice".to_string()];
+    assert_eq!(filter_login_users(PASSWD, None, &ui), ["alice", "carol"]);
+}